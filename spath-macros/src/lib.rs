@@ -0,0 +1,364 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Procedural macros for SPath
+//!
+//! Use these macros via the `macros` feature of the `spath` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::format_ident;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse_macro_input;
+use syn::visit_mut::VisitMut;
+use syn::FnArg;
+use syn::GenericParam;
+use syn::Ident;
+use syn::ItemFn;
+use syn::Lifetime;
+use syn::LitStr;
+use syn::ReturnType;
+use syn::Type;
+
+/// Define an SPath function with a plain Rust function.
+///
+/// The annotated function is kept as is. Besides, a struct with the same name is generated, whose
+/// `function()` method builds the [`Function`] definition to be registered with a registry:
+///
+/// ```ignore
+/// use spath::spath_function;
+/// use spath::spec::function::CustomFunctionRegistry;
+///
+/// #[spath_function]
+/// fn has_prefix(s: &str, prefix: &str) -> bool {
+///     s.starts_with(prefix)
+/// }
+///
+/// let mut registry = CustomFunctionRegistry::default();
+/// registry.register(has_prefix::function());
+/// ```
+///
+/// Each parameter is mapped to a declared argument type by its type:
+///
+/// * `NodesType` parameters are of nodes type;
+/// * `LogicalType` parameters are of logical type;
+/// * `PredicateType` parameters are of predicate type;
+/// * `ValueType` parameters are of value type, passed as is;
+/// * `Option<X>` parameters are of value type, [`None`] if the value is nothing or cannot be
+///   converted to `X`;
+/// * any other parameters are of value type converted with `FromValue`. If the value is nothing
+///   or cannot be converted, the function returns nothing without calling the Rust function.
+///
+/// The return type is mapped likewise: `bool` and `LogicalType` to logical type, `NodesType` to
+/// nodes type, and anything else, converted with `IntoValueType`, to value type.
+///
+/// The function may declare one type parameter for the variant value type. Otherwise, it's
+/// generic over any variant value type.
+///
+/// The name of the function in SPath queries defaults to the name of the Rust function, and can
+/// be overridden with `#[spath_function(name = "...")]`.
+///
+/// [`Function`]: https://docs.rs/spath/latest/spath/spec/function/struct.Function.html
+#[proc_macro_attribute]
+pub fn spath_function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut name = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported spath_function property"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+    let item = parse_macro_input!(item as ItemFn);
+
+    match expand(name, item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SPathType {
+    Nodes,
+    Value,
+    Logical,
+    Predicate,
+}
+
+impl SPathType {
+    fn classify_result(ty: &Type) -> Self {
+        match last_ident(ty) {
+            Some(ident) if ident == "bool" || ident == "LogicalType" => SPathType::Logical,
+            Some(ident) if ident == "NodesType" => SPathType::Nodes,
+            _ => SPathType::Value,
+        }
+    }
+
+    fn to_tokens(self) -> proc_macro2::TokenStream {
+        match self {
+            SPathType::Nodes => quote!(::spath::spec::function::SPathType::Nodes),
+            SPathType::Value => quote!(::spath::spec::function::SPathType::Value),
+            SPathType::Logical => quote!(::spath::spec::function::SPathType::Logical),
+            SPathType::Predicate => quote!(::spath::spec::function::SPathType::Predicate),
+        }
+    }
+
+    fn nothing(self) -> proc_macro2::TokenStream {
+        match self {
+            SPathType::Nodes => quote! {
+                ::spath::spec::function::SPathValue::Nodes(::spath::NodeList::new(::std::vec::Vec::new()))
+            },
+            SPathType::Value => quote!(::spath::spec::function::SPathValue::Nothing),
+            SPathType::Logical => quote! {
+                ::spath::spec::function::SPathValue::Logical(
+                    ::spath::spec::function::LogicalType::False,
+                )
+            },
+            SPathType::Predicate => unreachable!("predicate type is never a result type"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgKind {
+    Nodes,
+    Logical,
+    Predicate,
+    Value,
+    Optional,
+    Required,
+}
+
+impl ArgKind {
+    fn classify(ty: &Type) -> Self {
+        match last_ident(ty) {
+            Some(ident) if ident == "NodesType" => ArgKind::Nodes,
+            Some(ident) if ident == "LogicalType" => ArgKind::Logical,
+            Some(ident) if ident == "PredicateType" => ArgKind::Predicate,
+            Some(ident) if ident == "ValueType" => ArgKind::Value,
+            Some(ident) if ident == "Option" => ArgKind::Optional,
+            _ => ArgKind::Required,
+        }
+    }
+
+    fn spath_type(self) -> SPathType {
+        match self {
+            ArgKind::Nodes => SPathType::Nodes,
+            ArgKind::Logical => SPathType::Logical,
+            ArgKind::Predicate => SPathType::Predicate,
+            ArgKind::Value | ArgKind::Optional | ArgKind::Required => SPathType::Value,
+        }
+    }
+}
+
+fn last_ident(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last().map(|s| &s.ident),
+        Type::Group(group) => last_ident(&group.elem),
+        Type::Paren(paren) => last_ident(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Replace all lifetimes with `'_` so that the type can be used in a `let` binding.
+struct ElideLifetimes;
+
+impl VisitMut for ElideLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        *lifetime = Lifetime::new("'_", lifetime.span());
+    }
+}
+
+fn expand(name: Option<LitStr>, item: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let sig = &item.sig;
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "SPath functions cannot be async",
+        ));
+    }
+    if let Some(variadic) = &sig.variadic {
+        return Err(syn::Error::new_spanned(
+            variadic,
+            "SPath functions cannot be variadic",
+        ));
+    }
+
+    let ident = &sig.ident;
+    let vis = &item.vis;
+    let name = name.unwrap_or_else(|| LitStr::new(&ident.unraw().to_string(), ident.span()));
+
+    let mut type_params = sig.generics.params.iter().filter_map(|param| match param {
+        GenericParam::Type(param) => Some(param),
+        _ => None,
+    });
+    let (value_type, generic_param, turbofish) = match type_params.next() {
+        None => {
+            let value_type = Ident::new("__T", Span::call_site());
+            let param = quote!(#value_type: ::spath::VariantValue);
+            (value_type, param, quote!())
+        }
+        Some(param) => {
+            if let Some(extra) = type_params.next() {
+                return Err(syn::Error::new_spanned(
+                    extra,
+                    "SPath functions can have at most one type parameter for the variant value",
+                ));
+            }
+            let value_type = param.ident.clone();
+            (value_type.clone(), quote!(#param), quote!(::<#value_type>))
+        }
+    };
+    let where_clause = &sig.generics.where_clause;
+
+    let result_type = match &sig.output {
+        ReturnType::Default => {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "SPath functions must return a value",
+            ))
+        }
+        ReturnType::Type(_, ty) => SPathType::classify_result(ty),
+    };
+    let nothing = result_type.nothing();
+    let result_spath_type = result_type.to_tokens();
+
+    let mut argument_types = vec![];
+    let mut bindings = vec![];
+    let mut arguments = vec![];
+    for (i, input) in sig.inputs.iter().enumerate() {
+        let ty = match input {
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "SPath functions cannot take self",
+                ))
+            }
+            FnArg::Typed(typed) => &typed.ty,
+        };
+        let mut elided = (**ty).clone();
+        ElideLifetimes.visit_type_mut(&mut elided);
+
+        let kind = ArgKind::classify(ty);
+        argument_types.push(kind.spath_type().to_tokens());
+
+        let arg = format_ident!("__arg{i}");
+        let value = format_ident!("__value{i}");
+        let binding = match kind {
+            ArgKind::Nodes => quote! {
+                let #arg = args
+                    .next()
+                    .and_then(::spath::spec::function::SPathValue::into_nodes)
+                    .unwrap();
+            },
+            ArgKind::Logical => quote! {
+                let #arg = args
+                    .next()
+                    .and_then(::spath::spec::function::SPathValue::into_logical)
+                    .unwrap();
+            },
+            ArgKind::Predicate => quote! {
+                let #arg = args
+                    .next()
+                    .and_then(::spath::spec::function::SPathValue::into_predicate)
+                    .unwrap();
+            },
+            ArgKind::Value => quote! {
+                let #arg = args
+                    .next()
+                    .and_then(::spath::spec::function::SPathValue::into_value)
+                    .unwrap();
+            },
+            ArgKind::Optional => quote! {
+                let #value = args
+                    .next()
+                    .and_then(::spath::spec::function::SPathValue::into_value)
+                    .unwrap();
+                let #arg: #elided = #value
+                    .as_value()
+                    .and_then(::spath::spec::function::FromValue::from_value);
+            },
+            ArgKind::Required => quote! {
+                let #value = args
+                    .next()
+                    .and_then(::spath::spec::function::SPathValue::into_value)
+                    .unwrap();
+                let #arg: #elided = match #value
+                    .as_value()
+                    .and_then(::spath::spec::function::FromValue::from_value)
+                {
+                    Some(v) => v,
+                    None => return #nothing,
+                };
+            },
+        };
+        bindings.push(binding);
+        arguments.push(arg);
+    }
+
+    let result = match result_type {
+        SPathType::Nodes => quote! {
+            ::spath::spec::function::SPathValue::Nodes(::spath::NodeList::new(result.all()))
+        },
+        SPathType::Value => quote! {
+            ::spath::spec::function::SPathValue::from(
+                ::spath::spec::function::IntoValueType::into_value_type(result),
+            )
+        },
+        SPathType::Logical => quote! {
+            ::spath::spec::function::SPathValue::Logical(::core::convert::Into::<
+                ::spath::spec::function::LogicalType,
+            >::into(result))
+        },
+        SPathType::Predicate => unreachable!("predicate type is never a result type"),
+    };
+
+    let args = if arguments.is_empty() {
+        quote!(let _ = args;)
+    } else {
+        quote!(let mut args = args.into_iter();)
+    };
+
+    let doc = format!("The SPath function definition of [`{ident}`]({ident}()).");
+
+    Ok(quote! {
+        #item
+
+        #[doc = #doc]
+        #[allow(non_camel_case_types)]
+        #vis struct #ident {}
+
+        impl #ident {
+            /// Build the SPath function definition.
+            #vis fn function<#generic_param>() -> ::spath::spec::function::Function<#value_type>
+            #where_clause
+            {
+                ::spath::spec::function::Function::new(
+                    #name,
+                    vec![#(#argument_types),*],
+                    #result_spath_type,
+                    Box::new(move |args| {
+                        #args
+                        #(#bindings)*
+                        let result = #ident #turbofish(#(#arguments),*);
+                        #result
+                    }),
+                )
+            }
+        }
+    })
+}
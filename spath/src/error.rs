@@ -0,0 +1,217 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::ops::Range;
+
+use annotate_snippets::Level;
+use annotate_snippets::Renderer;
+use annotate_snippets::Snippet;
+
+use crate::spec::function::FunctionValidationError;
+use crate::spec::selector::filter::NonSingularQueryError;
+use crate::Extension;
+
+/// The most expected tokens to list in the annotation label; more are listed in a note.
+const MAX_EXPECTED_IN_LABEL: usize = 6;
+
+/// An error that can occur during parsing the SPath query.
+///
+/// Besides the [`Display`](fmt::Display) rendering of the annotated query, the error tells
+/// where in the query it is, what kind of error it is, and which tokens would have been valid
+/// there, e.g., to underline the span in a query editor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError(Box<ParseErrorRepr>);
+
+#[derive(Debug, Clone, PartialEq)]
+struct ParseErrorRepr {
+    source: String,
+    range: Range<usize>,
+    kind: ParseErrorKind,
+    message: Option<String>,
+    expected: Vec<String>,
+    help: Option<String>,
+}
+
+/// The kind of [`ParseError`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The query contains text that is no token, e.g., `$.a#b`.
+    Lexing,
+    /// A token is not valid at its position; see [`ParseError::expected`] for what would be.
+    UnexpectedToken,
+    /// The syntax belongs to an extension that is not enabled.
+    ExtensionNotEnabled(Extension),
+    /// A literal is malformed, e.g., an invalid escape sequence in a string or an integer
+    /// out of range.
+    InvalidLiteral,
+    /// A function is undefined or called with arguments of the wrong types.
+    FunctionValidation(FunctionValidationError),
+    /// A query is not singular where a singular query is required.
+    NonSingularQuery(NonSingularQueryError),
+    /// The query is otherwise invalid, e.g., it refers to an undefined `let` binding.
+    Invalid,
+}
+
+impl ParseErrorKind {
+    /// The name of the kind in snake case, e.g., `unexpected_token`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParseErrorKind::Lexing => "lexing",
+            ParseErrorKind::UnexpectedToken => "unexpected_token",
+            ParseErrorKind::ExtensionNotEnabled(_) => "extension_not_enabled",
+            ParseErrorKind::InvalidLiteral => "invalid_literal",
+            ParseErrorKind::FunctionValidation(_) => "function_validation",
+            ParseErrorKind::NonSingularQuery(_) => "non_singular_query",
+            ParseErrorKind::Invalid => "invalid",
+        }
+    }
+}
+
+impl ParseError {
+    pub(crate) fn new(
+        source: String,
+        range: Range<usize>,
+        kind: ParseErrorKind,
+        message: Option<String>,
+        expected: Vec<String>,
+        help: Option<String>,
+    ) -> Self {
+        Self(Box::new(ParseErrorRepr {
+            source,
+            range,
+            kind,
+            message,
+            expected,
+            help,
+        }))
+    }
+
+    /// The query that failed to parse.
+    pub fn query(&self) -> &str {
+        &self.0.source
+    }
+
+    /// The byte range of the offending text in the [`query`](Self::query).
+    ///
+    /// The range is empty if the query ends unexpectedly.
+    pub fn range(&self) -> Range<usize> {
+        self.0.range.clone()
+    }
+
+    /// The kind of the error.
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.0.kind
+    }
+
+    /// The message that labels the offending text, e.g., ``expected `]` ``.
+    pub fn message(&self) -> String {
+        match (&self.0.message, self.0.expected.as_slice()) {
+            (Some(message), _) => message.clone(),
+            (None, []) => "unexpected token".to_string(),
+            (None, expected) if expected.len() > MAX_EXPECTED_IN_LABEL => {
+                format!("expected one of {} possible tokens", expected.len())
+            }
+            (None, expected) => format!("expected {}", one_of(expected)),
+        }
+    }
+
+    /// The descriptions of the tokens that would have been valid at [`range`](Self::range),
+    /// e.g., `` `]` `` or `string literal`.
+    pub fn expected(&self) -> &[String] {
+        &self.0.expected
+    }
+
+    /// A suggestion to fix the query, e.g., ``did you mean `length`?``.
+    pub fn help(&self) -> Option<&str> {
+        self.0.help.as_deref()
+    }
+
+    /// Render the error like [`Display`](fmt::Display), with ANSI colors for terminals.
+    pub fn render_styled(&self) -> String {
+        self.render(Renderer::styled())
+    }
+
+    fn render(&self, renderer: Renderer) -> String {
+        let label = self.message();
+        let note = match (&self.0.message, self.0.expected.as_slice()) {
+            (None, expected) if expected.len() > MAX_EXPECTED_IN_LABEL => {
+                Some(format!("expected {}", one_of(expected)))
+            }
+            _ => None,
+        };
+
+        let mut message = Level::Error.title("failed to parse SPath query").snippet(
+            Snippet::source(self.query())
+                .annotation(Level::Error.span(self.range()).label(label.as_str())),
+        );
+        if let Some(note) = &note {
+            message = message.footer(Level::Note.title(note.as_str()));
+        }
+        if let Some(help) = self.help() {
+            message = message.footer(Level::Help.title(help));
+        }
+        let rendered = renderer.render(message).to_string();
+        rendered
+    }
+}
+
+fn one_of(expected: &[String]) -> String {
+    match expected {
+        [] => String::new(),
+        [expected] => expected.clone(),
+        [first, second] => format!("{first} or {second}"),
+        [expected @ .., last] => format!("one of {}, or {last}", expected.join(", ")),
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(Renderer::plain()))
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.kind() {
+            ParseErrorKind::FunctionValidation(err) => Some(err),
+            ParseErrorKind::NonSingularQuery(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Serialize the error as a map of its accessors, with the kind by its [name].
+///
+/// [name]: ParseErrorKind::name
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ParseError", 7)?;
+        state.serialize_field("kind", self.kind().name())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("query", self.query())?;
+        state.serialize_field("start", &self.0.range.start)?;
+        state.serialize_field("end", &self.0.range.end)?;
+        state.serialize_field("expected", self.expected())?;
+        state.serialize_field("help", &self.help())?;
+        state.end()
+    }
+}
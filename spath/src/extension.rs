@@ -0,0 +1,192 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// A syntax extension beyond [RFC 9535].
+///
+/// Extensions are disabled by default and must be enabled explicitly with [`Extensions`] when
+/// parsing a query. With no extension enabled, the parser is in the strict mode that rejects
+/// anything outside the ABNF of RFC 9535, including the lexical leniencies below, e.g., `<>` or
+/// `\x41`.
+///
+/// [RFC 9535]: https://datatracker.ietf.org/doc/html/rfc9535
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Extension {
+    /// The trailing `~` that selects the member names (or array indices) of the resulting
+    /// nodes, e.g., `$.features.*~`.
+    KeySelector,
+    /// The parent segment `^` that selects the parent of the preceding nodes, e.g.,
+    /// `$..[?@.isbn == '0-553-21311-3']^`.
+    ParentSegment,
+    /// Named parameters `$$name` bound at evaluation time, e.g., `$.users[?@.id == $$uid]`.
+    Parameters,
+    /// Array and object literals, e.g., `@.tags == ['a', 'b']` or `@.size == {'w': 1, 'h': 2}`.
+    CompositeLiterals,
+    /// The membership operators `in`, `nin` and `contains`, e.g., `@.status in ['open', 'done']`
+    /// or `@.tags contains 'x'`.
+    MembershipOperators,
+    /// Arithmetic `+`, `-`, `*`, `/` and `%` in comparisons, e.g., `@.price * @.qty > 100`.
+    Arithmetic,
+    /// The regex match operators `=~` and `!~` with a `/pattern/flags` literal, e.g.,
+    /// `@.name =~ /^foo/i`; requires the `regex` feature.
+    RegexMatch,
+    /// The descendant segment bounded by depth `..{min,max}`, e.g., `$..{1,2}name` selects the
+    /// `name` members of the children and grandchildren.
+    BoundedDescendant,
+    /// The sort selector that orders array elements by singular queries, optionally sliced,
+    /// e.g., `$.books[sort(@.price desc, @.title)[:3]]`.
+    Sort,
+    /// The trailing projection `.{...}` that constructs an object from each resulting node,
+    /// e.g., `$.users[*].{id: @.id, mail: @.contact.email}`.
+    Projection,
+    /// The union `|` that concatenates the results of whole queries, e.g.,
+    /// `$.errors[*] | $.warnings[?@.severe]`.
+    Union,
+    /// The pipeline `=>` that evaluates a query with each result of the preceding query as the
+    /// current node, e.g., `$.store.book[?@.price < 10] => @.author`.
+    Pipeline,
+    /// The member name (or array index) of the current filter candidate `@key`, e.g.,
+    /// `$.settings[?search(@key, '^feature_')]`.
+    CurrentKey,
+    /// The parent of the current filter candidate `@^`, e.g.,
+    /// `$.orders[*][?@.currency != @^.currency]`.
+    CurrentParent,
+    /// The `let` bindings preceding the query, each evaluated once and referenced as `$name`,
+    /// e.g., `let $max = max($.items[*].price); $.items[?@.price == $max]`.
+    LetBindings,
+    /// The alternative comparison operators `=` for `==` and `<>` for `!=`, e.g., `@.a = 1`.
+    AlternativeOperators,
+    /// String literals with the `\x41` and octal `\101` escape sequences, or with unescaped
+    /// control characters, e.g., a tab.
+    LenientStrings,
+    /// Digit separators `_` in integer literals, e.g., `1_000`.
+    DigitSeparators,
+    /// Integers with leading zeros, e.g., `007`, and indices and slice bounds beyond the range
+    /// of ±(2^53-1), or written as `-0`.
+    LenientIntegers,
+    /// Function names beyond the RFC 9535 syntax of lowercase letters, digits and `_`, e.g.,
+    /// `Length(@)`, matched case-insensitively by the builtin registries.
+    LenientFunctionNames,
+    /// Whitespace beyond the RFC 9535 rules, i.e., leading or trailing whitespace, form feeds,
+    /// and whitespace after `.` and `..` or between a function name and `(`, e.g., `$. name`.
+    LenientWhitespace,
+}
+
+impl Extension {
+    /// All the known extensions.
+    pub const ALL: &'static [Extension] = &[
+        Extension::KeySelector,
+        Extension::ParentSegment,
+        Extension::Parameters,
+        Extension::CompositeLiterals,
+        Extension::MembershipOperators,
+        Extension::Arithmetic,
+        Extension::RegexMatch,
+        Extension::BoundedDescendant,
+        Extension::Sort,
+        Extension::Projection,
+        Extension::Union,
+        Extension::Pipeline,
+        Extension::CurrentKey,
+        Extension::CurrentParent,
+        Extension::LetBindings,
+        Extension::AlternativeOperators,
+        Extension::LenientStrings,
+        Extension::DigitSeparators,
+        Extension::LenientIntegers,
+        Extension::LenientFunctionNames,
+        Extension::LenientWhitespace,
+    ];
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+impl fmt::Display for Extension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extension::KeySelector => write!(f, "key selector `~`"),
+            Extension::ParentSegment => write!(f, "parent segment `^`"),
+            Extension::Parameters => write!(f, "named parameter `$$name`"),
+            Extension::CompositeLiterals => write!(f, "composite literal"),
+            Extension::MembershipOperators => write!(f, "membership operator"),
+            Extension::Arithmetic => write!(f, "arithmetic"),
+            Extension::RegexMatch => write!(f, "regex match operator"),
+            Extension::BoundedDescendant => write!(f, "bounded descendant segment `..{{min,max}}`"),
+            Extension::Sort => write!(f, "sort selector"),
+            Extension::Projection => write!(f, "projection `.{{...}}`"),
+            Extension::Union => write!(f, "union `|`"),
+            Extension::Pipeline => write!(f, "pipeline `=>`"),
+            Extension::CurrentKey => write!(f, "current key `@key`"),
+            Extension::CurrentParent => write!(f, "current parent `@^`"),
+            Extension::LetBindings => write!(f, "let binding"),
+            Extension::AlternativeOperators => write!(f, "alternative operator `=` and `<>`"),
+            Extension::LenientStrings => write!(f, "lenient string literal"),
+            Extension::DigitSeparators => write!(f, "digit separator `_`"),
+            Extension::LenientIntegers => write!(f, "lenient integer"),
+            Extension::LenientFunctionNames => write!(f, "lenient function name"),
+            Extension::LenientWhitespace => write!(f, "lenient whitespace"),
+        }
+    }
+}
+
+/// A set of enabled [`Extension`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Extensions(u32);
+
+impl Extensions {
+    /// No extensions enabled, i.e., the strict mode where only the syntax defined by RFC 9535 is
+    /// accepted.
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    /// All the known extensions enabled.
+    pub fn all() -> Self {
+        Extension::ALL
+            .iter()
+            .fold(Self::none(), |extensions, ext| extensions.with(*ext))
+    }
+
+    /// Enable the given extension.
+    pub fn with(self, extension: Extension) -> Self {
+        Self(self.0 | extension.bit())
+    }
+
+    /// Disable the given extension.
+    pub fn without(self, extension: Extension) -> Self {
+        Self(self.0 & !extension.bit())
+    }
+
+    /// Whether the given extension is enabled.
+    pub fn contains(&self, extension: Extension) -> bool {
+        self.0 & extension.bit() != 0
+    }
+}
+
+impl From<Extension> for Extensions {
+    fn from(extension: Extension) -> Self {
+        Self::none().with(extension)
+    }
+}
+
+impl FromIterator<Extension> for Extensions {
+    fn from_iter<I: IntoIterator<Item = Extension>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::none(), |extensions, ext| extensions.with(ext))
+    }
+}
@@ -19,11 +19,13 @@ use serde_json::Value;
 use crate::spec::function;
 use crate::value::ConcreteVariantArray;
 use crate::value::ConcreteVariantObject;
+use crate::value::VariantBuilder;
 use crate::value::VariantValue;
 use crate::FromLiteral;
 use crate::Literal;
 
 pub type BuiltinFunctionRegistry = function::BuiltinFunctionRegistry<Value>;
+pub type CustomFunctionRegistry = function::CustomFunctionRegistry<Value>;
 
 impl FromLiteral for Value {
     fn from_literal(literal: Literal) -> Option<Self> {
@@ -33,6 +35,16 @@ impl FromLiteral for Value {
             Literal::String(v) => Some(Value::String(v)),
             Literal::Bool(v) => Some(Value::Bool(v)),
             Literal::Null => Some(Value::Null),
+            Literal::Array(elements) => elements
+                .into_iter()
+                .map(Value::from_literal)
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array),
+            Literal::Object(members) => members
+                .into_iter()
+                .map(|(name, value)| Value::from_literal(value).map(|v| (name, v)))
+                .collect::<Option<Map<_, _>>>()
+                .map(Value::Object),
         }
     }
 }
@@ -53,6 +65,10 @@ impl VariantValue for Value {
         self.is_string()
     }
 
+    fn is_number(&self) -> bool {
+        self.is_number()
+    }
+
     fn is_array(&self) -> bool {
         self.is_array()
     }
@@ -69,6 +85,14 @@ impl VariantValue for Value {
         self.as_str()
     }
 
+    fn as_i64(&self) -> Option<i64> {
+        self.as_i64()
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        self.as_f64()
+    }
+
     fn as_array(&self) -> Option<&Self::VariantArray> {
         self.as_array()
     }
@@ -108,11 +132,29 @@ impl VariantValue for Value {
 
         match (self, other) {
             (Value::Number(a), Value::Number(b)) => number_equal_to(a, b),
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(l, r)| l.is_equal_to(r))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(k, l)| b.get(k).is_some_and(|r| l.is_equal_to(r)))
+            }
             _ => self == other,
         }
     }
 }
 
+impl VariantBuilder for Value {
+    fn build_array(elements: Vec<Self>) -> Self {
+        Value::Array(elements)
+    }
+
+    fn build_object(members: Vec<(String, Self)>) -> Self {
+        Value::Object(members.into_iter().collect())
+    }
+}
+
 impl ConcreteVariantArray for Vec<Value> {
     type Value = Value;
 
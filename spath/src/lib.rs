@@ -16,9 +16,20 @@
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+mod error;
+pub use error::*;
+
+mod extension;
+pub use extension::*;
+
 mod node;
 pub use node::*;
 
+mod number;
+
+mod params;
+pub use params::*;
+
 mod path;
 pub use path::*;
 
@@ -32,37 +43,10 @@ pub use value::*;
 
 mod parser;
 
+#[cfg(feature = "macros")]
+pub use spath_macros::spath_function;
+
 #[cfg(feature = "json")]
 pub mod json;
 #[cfg(feature = "toml")]
 pub mod toml;
-
-/// An error that can occur during parsing the SPath query.
-#[derive(Debug)]
-pub struct ParseError {
-    source: String,
-    range: std::ops::Range<usize>,
-    message: String,
-}
-
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use annotate_snippets::Level;
-        use annotate_snippets::Renderer;
-        use annotate_snippets::Snippet;
-
-        let message = Level::Error.title("failed to parse SPath query").snippet(
-            Snippet::source(self.source.as_str()).annotation(
-                Level::Error
-                    .span(self.range.clone())
-                    .label(self.message.as_str()),
-            ),
-        );
-
-        let render = Renderer::plain();
-        write!(f, "{}", render.render(message))?;
-        Ok(())
-    }
-}
-
-impl std::error::Error for ParseError {}
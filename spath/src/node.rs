@@ -107,6 +107,118 @@ impl<'a, T: VariantValue> IntoIterator for NodeList<'a, T> {
     }
 }
 
+/// A list of owned values resulting from an SPath query.
+///
+/// Unlike [`NodeList`], each value is owned rather than borrowed from the queried value, e.g.,
+/// the objects constructed by a projection `.{...}`.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct OwnedNodeList<T: VariantValue>(Vec<T>);
+
+impl<T: VariantValue> OwnedNodeList<T> {
+    /// Create a new [`OwnedNodeList`] from a vector of values
+    pub fn new(values: Vec<T>) -> Self {
+        Self(values)
+    }
+
+    /// Extract all values yielded by the query
+    pub fn all(self) -> Vec<T> {
+        self.0
+    }
+
+    /// Get the length of an [`OwnedNodeList`]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if an [`OwnedNodeList`] is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get an iterator over an [`OwnedNodeList`]
+    ///
+    /// Note that [`OwnedNodeList`] also implements [`IntoIterator`].
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.0.iter()
+    }
+
+    /// Returns the first value in the [`OwnedNodeList`], or `None` if it is empty
+    pub fn first(&self) -> Option<&T> {
+        self.0.first()
+    }
+
+    /// Returns the value at the given index in the [`OwnedNodeList`], or `None` if the given
+    /// index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.0.get(index)
+    }
+}
+
+impl<T: VariantValue> IntoIterator for OwnedNodeList<T> {
+    type Item = T;
+
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// A node yielded by a query, either borrowed from the queried value or synthesized by the query.
+///
+/// Queries conforming to RFC 9535 only yield borrowed nodes. Extensions like the key selector
+/// `~` synthesize nodes that do not exist in the queried value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node<'a, T: VariantValue> {
+    /// A node borrowed from the queried value.
+    Borrowed(&'a T),
+    /// A node synthesized by the query.
+    Owned(T),
+}
+
+impl<'a, T: VariantValue> Node<'a, T> {
+    /// Get the node borrowed from the queried value, or `None` if the node is synthesized.
+    pub fn as_borrowed(&self) -> Option<&'a T> {
+        match self {
+            Node::Borrowed(node) => Some(node),
+            Node::Owned(_) => None,
+        }
+    }
+
+    /// Test if the node is synthesized by the query.
+    pub fn is_owned(&self) -> bool {
+        matches!(self, Node::Owned(_))
+    }
+
+    /// Take the node as an owned value, cloning it if it's borrowed.
+    pub fn into_owned(self) -> T
+    where
+        T: Clone,
+    {
+        match self {
+            Node::Borrowed(node) => node.clone(),
+            Node::Owned(node) => node,
+        }
+    }
+}
+
+impl<T: VariantValue> std::ops::Deref for Node<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Node::Borrowed(node) => node,
+            Node::Owned(node) => node,
+        }
+    }
+}
+
+impl<T: VariantValue> AsRef<T> for Node<'_, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
 /// A node within a variant value, along with its normalized path location.
 #[derive(Debug, Clone)]
 pub struct LocatedNode<'a, T: VariantValue> {
@@ -231,6 +343,20 @@ impl<'a, T: VariantValue> LocatedNodeList<'a, T> {
         self.0.dedup();
     }
 
+    /// Deduplicate a [`LocatedNodeList`] by location, keeping the first entry of each location
+    /// in order, e.g., to take the distinct nodes of a union `|`
+    ///
+    /// Unlike [`dedup`][LocatedNodeList::dedup], the order of the entries is preserved.
+    pub fn distinct(self) -> Self {
+        let mut seen = std::collections::BTreeSet::new();
+        let nodes = self
+            .0
+            .into_iter()
+            .filter(|node| seen.insert(node.loc.clone()))
+            .collect();
+        Self(nodes)
+    }
+
     /// Return the first entry in the [`LocatedNodeList`], or `None` if it is empty
     pub fn first(&self) -> Option<&LocatedNode<'a, T>> {
         self.0.first()
@@ -0,0 +1,145 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Numeric semantics shared by functions and operators over variant values.
+
+#[cfg(feature = "aggregate-functions")]
+use std::cmp::Ordering;
+
+#[cfg(feature = "aggregate-functions")]
+use num_cmp::NumCmp;
+
+use crate::Literal;
+use crate::VariantValue;
+
+/// A number read from a variant value.
+///
+/// Operations on two integers yield an integer, except for a division that is not exact, which
+/// yields a float; operations involving a float yield a float. Results that overflow, e.g.,
+/// `i64::MAX + 1`, or are not finite are [`None`], so that all backends agree on them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    pub fn from_value<T: VariantValue>(value: &T) -> Option<Self> {
+        match value.as_i64() {
+            Some(v) => Some(Number::Int(v)),
+            None => value.as_f64().map(Number::Float),
+        }
+    }
+
+    pub fn into_value<T: VariantValue>(self) -> Option<T> {
+        match self {
+            Number::Int(v) => T::from_literal(Literal::Int(v)),
+            Number::Float(v) => T::from_literal(Literal::Float(v)),
+        }
+    }
+
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(v) => v as f64,
+            Number::Float(v) => v,
+        }
+    }
+
+    #[cfg(feature = "math-functions")]
+    pub fn abs(self) -> Option<Self> {
+        match self {
+            Number::Int(v) => v.checked_abs().map(Number::Int),
+            Number::Float(v) => Some(Number::Float(v.abs())),
+        }
+    }
+
+    #[cfg(feature = "math-functions")]
+    pub fn floor(self) -> Self {
+        match self {
+            Number::Int(v) => Number::Int(v),
+            Number::Float(v) => Number::Float(v.floor()),
+        }
+    }
+
+    #[cfg(feature = "math-functions")]
+    pub fn ceil(self) -> Self {
+        match self {
+            Number::Int(v) => Number::Int(v),
+            Number::Float(v) => Number::Float(v.ceil()),
+        }
+    }
+
+    /// Round half away from zero.
+    #[cfg(feature = "math-functions")]
+    pub fn round(self) -> Self {
+        match self {
+            Number::Int(v) => Number::Int(v),
+            Number::Float(v) => Number::Float(v.round()),
+        }
+    }
+
+    pub fn add(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Number::Int(l), Number::Int(r)) => l.checked_add(r).map(Number::Int),
+            (l, r) => finite(l.as_f64() + r.as_f64()),
+        }
+    }
+
+    pub fn sub(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Number::Int(l), Number::Int(r)) => l.checked_sub(r).map(Number::Int),
+            (l, r) => finite(l.as_f64() - r.as_f64()),
+        }
+    }
+
+    pub fn mul(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Number::Int(l), Number::Int(r)) => l.checked_mul(r).map(Number::Int),
+            (l, r) => finite(l.as_f64() * r.as_f64()),
+        }
+    }
+
+    /// Divide self by other; integers that divide exactly yield an integer.
+    pub fn div(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Number::Int(_), Number::Int(0)) => None,
+            (Number::Int(l), Number::Int(r)) if l.checked_rem(r) == Some(0) => {
+                l.checked_div(r).map(Number::Int)
+            }
+            (l, r) => finite(l.as_f64() / r.as_f64()),
+        }
+    }
+
+    /// The remainder of self divided by other, with the sign of self.
+    pub fn rem(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Number::Int(l), Number::Int(r)) => l.checked_rem(r).map(Number::Int),
+            (l, r) => finite(l.as_f64() % r.as_f64()),
+        }
+    }
+
+    #[cfg(feature = "aggregate-functions")]
+    pub fn partial_cmp(self, other: Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(l), Number::Int(r)) => Some(l.cmp(&r)),
+            (Number::Int(l), Number::Float(r)) => NumCmp::num_cmp(l, r),
+            (Number::Float(l), Number::Int(r)) => NumCmp::num_cmp(l, r),
+            (Number::Float(l), Number::Float(r)) => l.partial_cmp(&r),
+        }
+    }
+}
+
+fn finite(v: f64) -> Option<Number> {
+    v.is_finite().then_some(Number::Float(v))
+}
@@ -0,0 +1,72 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use crate::Literal;
+use crate::VariantValue;
+
+/// Named parameters bound to an SPath query at evaluation time.
+///
+/// Parameters are referenced as `$$name` in queries parsed with [`Extension::Parameters`].
+///
+/// [`Extension::Parameters`]: crate::Extension::Parameters
+#[derive(Debug, Clone)]
+pub struct Params<T: VariantValue> {
+    values: BTreeMap<String, Option<T>>,
+}
+
+impl<T: VariantValue> Default for Params<T> {
+    fn default() -> Self {
+        Self {
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: VariantValue> Params<T> {
+    /// Create an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind the parameter `name` to a variant value.
+    pub fn bind(&mut self, name: impl Into<String>, value: T) -> &mut Self {
+        self.values.insert(name.into(), Some(value));
+        self
+    }
+
+    /// Bind the parameter `name` to a literal.
+    ///
+    /// The parameter evaluates to nothing if the literal cannot be represented as a variant value.
+    pub fn bind_literal(&mut self, name: impl Into<String>, literal: Literal) -> &mut Self {
+        self.values.insert(name.into(), T::from_literal(literal));
+        self
+    }
+
+    /// Whether the parameter `name` is bound.
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// The value bound to the parameter `name`, or `None` if it's unbound or nothing.
+    pub fn get(&self, name: &str) -> Option<&T> {
+        self.values.get(name).and_then(Option::as_ref)
+    }
+}
+
+/// An error that a parameter referenced by the query is not bound.
+#[derive(Debug, thiserror::Error)]
+#[error("parameter `$${0}` is not bound")]
+pub struct UnboundParamError(pub String);
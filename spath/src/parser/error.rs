@@ -24,12 +24,18 @@ use crate::parser::range::Range;
 use crate::spec::function::FunctionValidationError;
 use crate::spec::selector::filter::NonSingularQueryError;
 use crate::ParseError;
+use crate::ParseErrorKind;
 
 /// An in-flight parsing error.
 #[derive(Debug)]
 pub struct Error {
     range: Range,
-    message: String,
+    kind: Box<ParseErrorKind>,
+    /// The error message; [`None`] if the token is merely unexpected.
+    message: Option<String>,
+    /// The descriptions of the tokens expected at `range`, in the order they were tried.
+    expected: Vec<String>,
+    help: Option<String>,
     cut: bool,
 }
 
@@ -39,7 +45,10 @@ impl<'a, Registry> ParserError<Input<'a, Registry>> for Error {
     fn from_input(input: &Input<'a, Registry>) -> Self {
         Self {
             range: input[0].span,
-            message: "unexpected token".to_string(),
+            kind: Box::new(ParseErrorKind::UnexpectedToken),
+            message: None,
+            expected: vec![],
+            help: None,
             cut: false,
         }
     }
@@ -48,11 +57,7 @@ impl<'a, Registry> ParserError<Input<'a, Registry>> for Error {
     where
         Input<'a, Registry>: Debug,
     {
-        Self {
-            range: input[0].span,
-            message: message.to_string(),
-            cut: true,
-        }
+        Self::new_cut(input[0].span, message)
     }
 
     fn incomplete(_input: &Input<'a, Registry>, _needed: Needed) -> Self {
@@ -61,9 +66,33 @@ impl<'a, Registry> ParserError<Input<'a, Registry>> for Error {
 
     fn or(self, other: Self) -> Self {
         if self.cut {
-            self
-        } else {
-            other
+            return self;
+        }
+        if other.cut || other.range.start > self.range.start {
+            return other;
+        }
+        if other.range.start < self.range.start {
+            return self;
+        }
+
+        // both alternatives failed at the same token, so any of their expectations would do
+        let mut expected = self.expected;
+        for what in other.expected {
+            if !expected.contains(&what) {
+                expected.push(what);
+            }
+        }
+        let (kind, message) = match other.message {
+            Some(message) => (other.kind, Some(message)),
+            None => (self.kind, self.message),
+        };
+        Self {
+            range: other.range,
+            kind,
+            message,
+            expected,
+            help: other.help.or(self.help),
+            cut: false,
         }
     }
 
@@ -84,25 +113,17 @@ impl<'a, Registry> FromExternalError<Input<'a, Registry>, Error> for Error {
 
 impl<'a, Registry> FromExternalError<Input<'a, Registry>, FunctionValidationError> for Error {
     fn from_external_error(input: &Input<'a, Registry>, err: FunctionValidationError) -> Self {
-        let range = input[0].span;
-        let message = format!("{err}");
-        Self {
-            range,
-            message,
-            cut: true,
-        }
+        let message = err.to_string();
+        Self::new_cut(input[0].span, message).with_kind(ParseErrorKind::FunctionValidation(err))
     }
 }
 
 impl<'a, Registry> FromExternalError<Input<'a, Registry>, NonSingularQueryError> for Error {
     fn from_external_error(input: &Input<'a, Registry>, err: NonSingularQueryError) -> Self {
-        let range = input[0].span;
-        let message = format!("{err}");
-        Self {
-            range,
-            message,
-            cut: false,
-        }
+        let message = err.to_string();
+        Self::new_cut(input[0].span, message)
+            .with_kind(ParseErrorKind::NonSingularQuery(err))
+            .backtrack()
     }
 }
 
@@ -122,21 +143,129 @@ impl Error {
     pub fn new_cut(range: Range, message: impl Into<String>) -> Self {
         Self {
             range,
-            message: message.into(),
+            kind: Box::new(ParseErrorKind::Invalid),
+            message: Some(message.into()),
+            expected: vec![],
+            help: None,
             cut: true,
         }
     }
 
+    /// An error that any of the `expected` tokens should have been at `range`.
+    pub fn new_expected(range: Range, expected: Vec<String>) -> Self {
+        Self {
+            range,
+            kind: Box::new(ParseErrorKind::UnexpectedToken),
+            message: None,
+            expected,
+            help: None,
+            cut: false,
+        }
+    }
+
+    /// An error of a malformed literal, e.g., an invalid escape sequence in a string.
+    pub fn invalid_literal(range: Range, message: impl Into<String>) -> Self {
+        Self::new_cut(range, message).with_kind(ParseErrorKind::InvalidLiteral)
+    }
+
+    pub fn is_cut(&self) -> bool {
+        self.cut
+    }
+
+    /// Replace the message, which then describes the error in place of the expected tokens.
     pub fn with_message(mut self, message: impl Into<String>) -> Self {
-        self.message = message.into();
+        self.message = Some(message.into());
+        self.expected.clear();
+        self
+    }
+
+    pub fn with_kind(mut self, kind: ParseErrorKind) -> Self {
+        self.kind = Box::new(kind);
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Forget the expected tokens, e.g., those of a syntax whose extension is not enabled.
+    pub fn without_expected(mut self) -> Self {
+        self.expected.clear();
         self
     }
 
     pub fn into_parse_error(self, source: impl Into<String>) -> ParseError {
-        ParseError {
-            source: source.into(),
-            range: self.range.into(),
-            message: self.message,
+        ParseError::new(
+            source.into(),
+            self.range.into(),
+            *self.kind,
+            self.message,
+            self.expected,
+            self.help,
+        )
+    }
+}
+
+/// Find the candidate closest to `name` by edit distance, if any is close enough to be a typo.
+pub fn did_you_mean<'c>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'c str>,
+) -> Option<&'c str> {
+    let name = name.to_lowercase();
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between `a` and `b`, counting a transposition as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // rows of the distance matrix for a[..i - 1], a[..i], and a[..i + 1]
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                curr[j] = curr[j].min(prev2[j - 2] + 1);
+            }
         }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::did_you_mean;
+    use super::edit_distance;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("length", "length"), 0);
+        assert_eq!(edit_distance("lenght", "length"), 1);
+        assert_eq!(edit_distance("lengt", "length"), 1);
+        assert_eq!(edit_distance("count", "value"), 5);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = ["count", "length", "match", "search", "value"];
+        assert_eq!(did_you_mean("lenght", candidates), Some("length"));
+        assert_eq!(did_you_mean("Count", candidates), Some("count"));
+        assert_eq!(did_you_mean("serch", candidates), Some("search"));
+        assert_eq!(did_you_mean("foo", candidates), None);
     }
 }
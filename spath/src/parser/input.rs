@@ -15,7 +15,7 @@
 use std::fmt;
 use std::sync::Arc;
 
-use winnow::error::ParserError;
+use winnow::error::ModalError;
 use winnow::stream::Stream;
 use winnow::Parser;
 use winnow::Stateful;
@@ -25,10 +25,18 @@ use crate::parser::range::Range;
 use crate::parser::token::Token;
 use crate::parser::token::TokenKind;
 use crate::spec::function::FunctionRegistry;
+use crate::Extensions;
+use crate::ParseErrorKind;
 
 #[derive(Clone)]
 pub struct InputState<Registry> {
     registry: Arc<Registry>,
+    extensions: Extensions,
+    bindings: Vec<String>,
+    /// The furthest token any parser failed at, and the descriptions of what was expected there.
+    expected: Option<(Range, Vec<String>)>,
+    /// Whether to not record expectations, e.g., for syntaxes of disabled extensions.
+    quiet: bool,
 }
 
 impl<Registry> fmt::Debug for InputState<Registry> {
@@ -38,13 +46,58 @@ impl<Registry> fmt::Debug for InputState<Registry> {
 }
 
 impl<Registry> InputState<Registry> {
-    pub fn new(registry: Arc<Registry>) -> Self {
-        Self { registry }
+    pub fn new(registry: Arc<Registry>, extensions: Extensions) -> Self {
+        Self {
+            registry,
+            extensions,
+            bindings: vec![],
+            expected: None,
+            quiet: false,
+        }
     }
 
     pub fn registry(&self) -> Arc<Registry> {
         self.registry.clone()
     }
+
+    pub fn extensions(&self) -> Extensions {
+        self.extensions
+    }
+
+    /// Bring the `let` binding `name` into scope for the rest of the query.
+    pub fn bind(&mut self, name: String) {
+        self.bindings.push(name);
+    }
+
+    pub fn is_bound(&self, name: &str) -> bool {
+        self.bindings.iter().any(|n| n == name)
+    }
+
+    /// Record that `what` was expected at `range`, if no parser failed further yet.
+    pub fn expect(&mut self, range: Range, what: &str) {
+        if self.quiet {
+            return;
+        }
+        match &mut self.expected {
+            Some((at, expected)) if at.start == range.start => {
+                if !expected.iter().any(|e| e == what) {
+                    expected.push(what.to_string());
+                }
+            }
+            Some((at, _)) if at.start > range.start => {}
+            _ => self.expected = Some((range, vec![what.to_string()])),
+        }
+    }
+
+    /// The furthest token any parser failed at, and what was expected there.
+    pub fn take_expected(&mut self) -> Option<(Range, Vec<String>)> {
+        self.expected.take()
+    }
+
+    /// Set whether to record expectations, returning the previous setting.
+    pub fn set_quiet(&mut self, quiet: bool) -> bool {
+        std::mem::replace(&mut self.quiet, quiet)
+    }
 }
 
 pub type TokenSlice<'a> = winnow::stream::TokenSlice<'a, Token<'a>>;
@@ -63,16 +116,24 @@ where
                 Ok(token)
             }
             None => {
+                let span = input[0].span;
+                input.state.expect(span, self.describe());
                 if self.is_eoi() {
-                    let start = input.first().unwrap().span.start;
-                    let end = input.last().unwrap().span.end;
-                    Err(Error::new_cut(
-                        Range::from(start..end),
-                        "failed to parse the rest of input",
-                    ))
+                    // report the furthest failure, since what failed to parse may well be
+                    // an earlier query segment, e.g., `$.a[1,]`, rather than this token
+                    match input.state.take_expected() {
+                        Some((range, expected)) => Err(Error::new_expected(range, expected).cut()),
+                        None => {
+                            let end = input.last().unwrap().span.end;
+                            Err(Error::new_cut(
+                                Range::from(span.start..end),
+                                "failed to parse the rest of input",
+                            )
+                            .with_kind(ParseErrorKind::UnexpectedToken))
+                        }
+                    }
                 } else {
-                    let err = Error::from_input(input);
-                    Err(err.with_message(format!("expected token {self:?}")))
+                    Err(Error::new_expected(span, vec![self.describe().to_string()]))
                 }
             }
         }
@@ -93,8 +154,10 @@ where
             Ok(token)
         }
         None => {
-            let err = Error::from_input(input);
-            Err(err.with_message(format!("expected text {text}")))
+            let span = input[0].span;
+            let what = format!("`{text}`");
+            input.state.expect(span, &what);
+            Err(Error::new_expected(span, vec![what]))
         }
     }
 }
@@ -12,22 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::iter::Peekable;
+#[cfg(feature = "regex")]
+use std::sync::Arc;
 
 use winnow::combinator::alt;
 use winnow::combinator::backtrack_err;
+use winnow::combinator::cut_err;
 use winnow::combinator::delimited;
 use winnow::combinator::opt;
+use winnow::combinator::peek;
 use winnow::combinator::preceded;
 use winnow::combinator::repeat;
 use winnow::combinator::separated;
 use winnow::combinator::separated_pair;
+use winnow::combinator::terminated;
 use winnow::error::ModalError;
 use winnow::Parser;
 
+use crate::parser::error::did_you_mean;
 use crate::parser::error::Error;
 use crate::parser::input::text;
 use crate::parser::input::Input;
+use crate::parser::range::Range;
 use crate::parser::token::Token;
 use crate::parser::token::TokenKind::*;
 use crate::spec::function::FunctionExpr;
@@ -35,11 +43,19 @@ use crate::spec::function::FunctionExprArg;
 use crate::spec::function::FunctionRegistry;
 use crate::spec::function::FunctionValidationError;
 use crate::spec::function::SPathType;
+use crate::spec::query::Binding;
+use crate::spec::query::MainQuery;
+use crate::spec::query::Projection;
+use crate::spec::query::ProjectionMember;
+use crate::spec::query::ProjectionValue;
 use crate::spec::query::Query;
+use crate::spec::query::QueryBranch;
 use crate::spec::query::QueryKind;
 use crate::spec::segment::QuerySegment;
 use crate::spec::segment::QuerySegmentKind;
 use crate::spec::segment::Segment;
+use crate::spec::selector::filter::ArithmeticExpr;
+use crate::spec::selector::filter::ArithmeticOperator;
 use crate::spec::selector::filter::BasicExpr;
 use crate::spec::selector::filter::Comparable;
 use crate::spec::selector::filter::ComparisonExpr;
@@ -48,27 +64,232 @@ use crate::spec::selector::filter::ExistExpr;
 use crate::spec::selector::filter::Filter;
 use crate::spec::selector::filter::LogicalAndExpr;
 use crate::spec::selector::filter::LogicalOrExpr;
+#[cfg(feature = "regex")]
+use crate::spec::selector::filter::MatchExpr;
+#[cfg(feature = "regex")]
+use crate::spec::selector::filter::RegexLiteral;
 use crate::spec::selector::filter::SingularQuery;
 use crate::spec::selector::index::Index;
 use crate::spec::selector::name::Name;
 use crate::spec::selector::slice::Slice;
+use crate::spec::selector::sort::Sort;
+use crate::spec::selector::sort::SortKey;
 use crate::spec::selector::Selector;
+use crate::Extension;
+use crate::Extensions;
 use crate::Literal;
+use crate::ParseErrorKind;
+use crate::VariantValue;
 
-pub fn parse_query_main<Registry>(input: &mut Input<Registry>) -> Result<Query, Error>
+pub fn parse_query_main<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<MainQuery<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    (parse_root_query, EOI)
-        .map(|(query, _)| query)
-        .parse_next(input)
+    ensure_rfc_whitespace(input)?;
+
+    let params = input
+        .iter()
+        .filter(|token| token.kind == Parameter)
+        .map(|token| parameter_name(token).to_string())
+        .collect();
+
+    let (bindings, first, rest, _) = (
+        repeat(0.., parse_binding),
+        parse_query_branch,
+        repeat(
+            0..,
+            preceded(parse_union_operator, cut_err(parse_query_branch)),
+        ),
+        EOI,
+    )
+        .parse_next(input)?;
+    let mut branches = vec![first];
+    branches.extend::<Vec<_>>(rest);
+    Ok(MainQuery {
+        bindings,
+        branches,
+        params,
+    })
+}
+
+fn parse_binding<Registry>(input: &mut Input<Registry>) -> Result<Binding<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::LetBindings, text("let")).parse_next(input)?;
+    ensure_extension(input, token, Extension::LetBindings)?;
+
+    let (name, _, value, _) =
+        cut_err((BindingName, text("="), parse_arithmetic_expr, Semicolon)).parse_next(input)?;
+    let text = binding_name(name);
+    if input.state.is_bound(text) {
+        let message = format!("binding `${text}` is already defined");
+        return Err(Error::new_cut(name.span, message));
+    }
+    // the binding is in scope after its definition
+    input.state.bind(text.to_string());
+    Ok(Binding {
+        name: text.to_string(),
+        value,
+    })
+}
+
+fn parse_union_operator<Registry>(input: &mut Input<Registry>) -> Result<(), Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::Union, Pipe).parse_next(input)?;
+    ensure_extension(input, token, Extension::Union)
+}
+
+fn parse_pipeline_operator<Registry>(input: &mut Input<Registry>) -> Result<(), Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::Pipeline, Arrow).parse_next(input)?;
+    ensure_extension(input, token, Extension::Pipeline)
 }
 
-fn parse_root_query<Registry>(input: &mut Input<Registry>) -> Result<Query, Error>
+fn parse_query_branch<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<QueryBranch<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let (query, pipeline, suffix) = (
+        parse_root_query,
+        repeat(
+            0..,
+            preceded(parse_pipeline_operator, cut_err(parse_current_query)),
+        ),
+        opt(alt((
+            parse_key_selector.map(|_| None),
+            parse_projection.map(Some),
+        ))),
+    )
+        .parse_next(input)?;
+    Ok(QueryBranch {
+        query,
+        pipeline,
+        keys: matches!(suffix, Some(None)),
+        projection: suffix.flatten(),
+    })
+}
+
+fn parse_projection<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Projection<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    (text("$"), parse_path_segments)
+    let (_, token) = extension_syntax(Extension::Projection, (Dot, LBrace)).parse_next(input)?;
+    ensure_extension(input, token, Extension::Projection)?;
+
+    let extensions = input.state.extensions();
+    let name = alt((
+        Identifier.map(|name: &Token| name.text().to_string()),
+        LiteralString.try_map(move |token| parse_string(token, extensions)),
+    ));
+    let value = alt((
+        parse_query.map(ProjectionValue::Query),
+        parse_literal.map(ProjectionValue::Literal),
+    ));
+    let member = separated_pair(name, text(":"), value)
+        .map(|(name, value)| ProjectionMember { name, value });
+    let members: Vec<ProjectionMember<Registry::Value>> =
+        cut_err(terminated(separated(1.., member, text(",")), RBrace)).parse_next(input)?;
+    for (i, member) in members.iter().enumerate() {
+        if members[..i].iter().any(|m| m.name == member.name) {
+            let name = &member.name;
+            let message = format!("duplicate member name '{name}' in projection");
+            return Err(Error::new_cut(token.span, message));
+        }
+    }
+    Ok(Projection { members })
+}
+
+fn parse_key_selector<Registry>(input: &mut Input<Registry>) -> Result<(), Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::KeySelector, Tilde).parse_next(input)?;
+    ensure_extension(input, token, Extension::KeySelector)
+}
+
+/// Parse the tokens introducing a syntax of `extension`, which error messages only tell to be
+/// expected if the extension is enabled.
+fn extension_syntax<'a, Registry, O>(
+    extension: Extension,
+    mut parser: impl Parser<Input<'a, Registry>, O, Error>,
+) -> impl Parser<Input<'a, Registry>, O, Error>
+where
+    Registry: FunctionRegistry,
+{
+    move |input: &mut Input<'a, Registry>| {
+        if input.state.extensions().contains(extension) {
+            return parser.parse_next(input);
+        }
+        let quiet = input.state.set_quiet(true);
+        let result = parser.parse_next(input);
+        input.state.set_quiet(quiet);
+        result.map_err(Error::without_expected)
+    }
+}
+
+fn ensure_extension<Registry>(
+    input: &Input<Registry>,
+    token: &Token,
+    extension: Extension,
+) -> Result<(), Error> {
+    check_extension(input.state.extensions(), token.span, extension)
+}
+
+fn check_extension(extensions: Extensions, span: Range, extension: Extension) -> Result<(), Error> {
+    if extensions.contains(extension) {
+        Ok(())
+    } else {
+        let message = format!("the {extension} extension is not enabled");
+        Err(Error::new_cut(span, message).with_kind(ParseErrorKind::ExtensionNotEnabled(extension)))
+    }
+}
+
+/// Ensure no whitespace between the two tokens, where RFC 9535 allows none.
+fn ensure_adjacent<Registry>(
+    input: &Input<Registry>,
+    left: &Token,
+    right: &Token,
+) -> Result<(), Error> {
+    if left.span.end == right.span.start {
+        Ok(())
+    } else {
+        let span = Range::from(left.span.end..right.span.start);
+        check_extension(input.state.extensions(), span, Extension::LenientWhitespace)
+    }
+}
+
+/// Ensure no whitespace that RFC 9535 allows nowhere, i.e., leading or trailing whitespace of
+/// the query, and whitespace other than spaces, tabs, and line breaks.
+fn ensure_rfc_whitespace<Registry>(input: &Input<Registry>) -> Result<(), Error> {
+    let mut end = 0;
+    for token in input.iter() {
+        let gap = &token.source[end..token.span.start];
+        let leading_or_trailing = end == 0 || token.kind == EOI;
+        if !gap.is_empty() && (leading_or_trailing || gap.contains('\x0C')) {
+            let span = Range::from(end..token.span.start);
+            check_extension(input.state.extensions(), span, Extension::LenientWhitespace)?;
+        }
+        end = token.span.end;
+    }
+    Ok(())
+}
+
+fn parse_root_query<Registry>(input: &mut Input<Registry>) -> Result<Query<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    (text("$"), parse_root_path_segments)
         .map(|(_, segments)| Query {
             kind: QueryKind::Root,
             segments,
@@ -76,22 +297,46 @@ where
         .parse_next(input)
 }
 
-fn parse_path_segments<Registry>(input: &mut Input<Registry>) -> Result<Vec<QuerySegment>, Error>
+fn parse_path_segments<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Vec<QuerySegment<Registry::Value>>, Error>
 where
     Registry: FunctionRegistry,
 {
     repeat(0.., parse_segment).parse_next(input)
 }
 
-fn parse_segment<Registry>(input: &mut Input<Registry>) -> Result<QuerySegment, Error>
+fn parse_root_path_segments<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Vec<QuerySegment<Registry::Value>>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    repeat(0.., alt((parse_segment, parse_parent_segment))).parse_next(input)
+}
+
+fn parse_parent_segment<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<QuerySegment<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::ParentSegment, Caret).parse_next(input)?;
+    ensure_extension(input, token, Extension::ParentSegment)?;
+    Ok(QuerySegment {
+        kind: QuerySegmentKind::Child,
+        segment: Segment::Parent,
+    })
+}
+
+fn parse_segment<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<QuerySegment<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     alt((
-        parse_descendant_segment.map(|segment| QuerySegment {
-            kind: QuerySegmentKind::Descendant,
-            segment,
-        }),
+        parse_descendant_segment.map(|(kind, segment)| QuerySegment { kind, segment }),
         parse_child_segment.map(|segment| QuerySegment {
             kind: QuerySegmentKind::Child,
             segment,
@@ -100,22 +345,56 @@ where
     .parse_next(input)
 }
 
-fn parse_descendant_segment<Registry>(input: &mut Input<Registry>) -> Result<Segment, Error>
+fn parse_descendant_segment<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<(QuerySegmentKind, Segment<Registry::Value>), Error>
 where
     Registry: FunctionRegistry,
 {
-    preceded(
-        text(".."),
+    let dots = text("..").parse_next(input)?;
+    if let Some(next) = input.first() {
+        ensure_adjacent(input, dots, next)?;
+    }
+    (
+        opt(parse_descendant_depth),
         alt((
             parse_wildcard_selector.map(|_| Segment::Wildcard),
             parse_child_long_hand,
             parse_dot_member_name.map(Segment::DotName),
         )),
     )
-    .parse_next(input)
+        .map(|(kind, segment)| (kind.unwrap_or(QuerySegmentKind::Descendant), segment))
+        .parse_next(input)
 }
 
-fn parse_child_segment<Registry>(input: &mut Input<Registry>) -> Result<Segment, Error>
+fn parse_descendant_depth<Registry>(input: &mut Input<Registry>) -> Result<QuerySegmentKind, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let lbrace = extension_syntax(Extension::BoundedDescendant, LBrace).parse_next(input)?;
+    ensure_extension(input, lbrace, Extension::BoundedDescendant)?;
+
+    let depth = || LiteralInteger.try_map(parse_depth);
+    let (min, max, rbrace) =
+        cut_err((depth(), opt(preceded(text(","), opt(depth()))), RBrace)).parse_next(input)?;
+    // `{n}` is short for `{n,n}`, and `{n,}` is unbounded
+    let max = max.unwrap_or(Some(min));
+
+    let span = Range::from(lbrace.span.start..rbrace.span.end);
+    if min == 0 {
+        let message = "the minimum depth must be at least 1";
+        return Err(Error::new_cut(span, message));
+    }
+    if max.is_some_and(|max| max < min) {
+        let message = "the maximum depth must not be less than the minimum depth";
+        return Err(Error::new_cut(span, message));
+    }
+    Ok(QuerySegmentKind::BoundedDescendant { min, max })
+}
+
+fn parse_child_segment<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Segment<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -127,7 +406,9 @@ where
     .parse_next(input)
 }
 
-fn parse_child_long_hand<Registry>(input: &mut Input<Registry>) -> Result<Segment, Error>
+fn parse_child_long_hand<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Segment<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -139,28 +420,51 @@ where
     .parse_next(input)
 }
 
-fn parse_multi_selector<Registry>(input: &mut Input<Registry>) -> Result<Vec<Selector>, Error>
+fn parse_multi_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Vec<Selector<Registry::Value>>, Error>
 where
     Registry: FunctionRegistry,
 {
     separated(1.., parse_selector, text(",")).parse_next(input)
 }
 
-fn parse_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector, Error>
+fn parse_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    alt((
+    let selector = alt((
         parse_wildcard_selector,
         parse_name_selector,
         parse_array_slice_selector,
         parse_index_selector,
         parse_filter_selector,
+        parse_sort_selector,
     ))
-    .parse_next(input)
+    .parse_next(input);
+
+    selector.map_err(|err| {
+        if err.is_cut() {
+            return err;
+        }
+        match input.first().map(|token| token.kind) {
+            // a script expression of Goessner's JSONPath, e.g., `$.a[(@.length-1)]`
+            Some(LParen) => err.cut().with_help(
+                "script expressions are not supported; \
+                 did you mean a filter selector like `[?(@.b == 1)]`?",
+            ),
+            // a filter expression without the `?`, e.g., `$.a[@.b == 1]`
+            Some(At | Not) => err
+                .cut()
+                .with_help("filter selectors start with `?`, e.g., `[?@.b == 1]`"),
+            _ => err,
+        }
+    })
 }
 
-fn parse_index_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector, Error>
+fn parse_index_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Selector<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -171,13 +475,16 @@ fn parse_index<Registry>(input: &mut Input<Registry>) -> Result<Index, Error>
 where
     Registry: FunctionRegistry,
 {
+    let extensions = input.state.extensions();
     LiteralInteger
-        .try_map(parse_integer)
+        .try_map(move |token| parse_index_integer(token, extensions))
         .map(Index::new)
         .parse_next(input)
 }
 
-fn parse_array_slice_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector, Error>
+fn parse_array_slice_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Selector<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -190,6 +497,7 @@ fn parse_array_slice<Registry>(input: &mut Input<Registry>) -> Result<Slice, Err
 where
     Registry: FunctionRegistry,
 {
+    let extensions = input.state.extensions();
     separated_pair(
         opt(LiteralInteger),
         text(":"),
@@ -199,47 +507,80 @@ where
         )),
     )
     .try_map(|(start, (end, step))| {
-        let start = start.map(|i| parse_integer(i)).transpose()?;
-        let end = end.map(|i| parse_integer(i)).transpose()?;
-        let step = step.map(|i| parse_integer(i)).transpose()?;
+        let parse = |i| parse_index_integer(i, extensions);
+        let start = start.map(parse).transpose()?;
+        let end = end.map(parse).transpose()?;
+        let step = step.map(parse).transpose()?;
         Ok::<Slice, Error>(Slice::new(start, end, step))
     })
     .parse_next(input)
 }
 
-fn parse_name_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector, Error>
+fn parse_sort_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Selector<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
+    let (token, _) = extension_syntax(Extension::Sort, (text("sort"), LParen)).parse_next(input)?;
+    ensure_extension(input, token, Extension::Sort)?;
+
+    let key = (
+        parse_singular_path,
+        opt(alt((text("asc").value(false), text("desc").value(true)))),
+    )
+        .map(|(query, descending)| SortKey {
+            query,
+            descending: descending.unwrap_or(false),
+        });
+    let slice = delimited(LBracket, parse_array_slice, RBracket);
+    cut_err((separated(1.., key, text(",")), RParen, opt(slice)))
+        .map(|(keys, _, slice)| Selector::Sort(Sort::new(keys, slice)))
+        .parse_next(input)
+}
+
+fn parse_name_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Selector<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let extensions = input.state.extensions();
     LiteralString
-        .try_map(parse_string)
+        .try_map(move |token| parse_string(token, extensions))
         .map(|name| Selector::Name(Name::new(name)))
         .parse_next(input)
 }
 
-fn parse_wildcard_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector, Error>
+fn parse_wildcard_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Selector<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     text("*").map(|_| Selector::Wildcard).parse_next(input)
 }
 
-fn parse_dot_wildcard_shorthand<Registry>(input: &mut Input<Registry>) -> Result<Segment, Error>
+fn parse_dot_wildcard_shorthand<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Segment<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    preceded(text("."), parse_wildcard_selector)
-        .map(|_| Segment::Wildcard)
-        .parse_next(input)
+    let (dot, wildcard) = (text("."), text("*")).parse_next(input)?;
+    ensure_adjacent(input, dot, wildcard)?;
+    Ok(Segment::Wildcard)
 }
 
-fn parse_dot_member_name_shorthand<Registry>(input: &mut Input<Registry>) -> Result<Segment, Error>
+fn parse_dot_member_name_shorthand<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Segment<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    preceded(text("."), parse_dot_member_name)
-        .map(Segment::DotName)
-        .parse_next(input)
+    let (dot, name) = (text("."), alt((Identifier, TRUE, FALSE, NULL))).parse_next(input)?;
+    ensure_adjacent(input, dot, name)?;
+    Ok(Segment::DotName(name.text().to_string()))
 }
 
 fn parse_dot_member_name<Registry>(input: &mut Input<Registry>) -> Result<String, Error>
@@ -251,14 +592,16 @@ where
         .parse_next(input)
 }
 
-fn parse_filter_selector<Registry>(input: &mut Input<Registry>) -> Result<Selector, Error>
+fn parse_filter_selector<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Selector<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     parse_filter.map(Selector::Filter).parse_next(input)
 }
 
-fn parse_filter<Registry>(input: &mut Input<Registry>) -> Result<Filter, Error>
+fn parse_filter<Registry>(input: &mut Input<Registry>) -> Result<Filter<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -267,7 +610,9 @@ where
         .parse_next(input)
 }
 
-fn parse_logical_or_expr<Registry>(input: &mut Input<Registry>) -> Result<LogicalOrExpr, Error>
+fn parse_logical_or_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<LogicalOrExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -276,7 +621,9 @@ where
         .parse_next(input)
 }
 
-fn parse_logical_and<Registry>(input: &mut Input<Registry>) -> Result<LogicalAndExpr, Error>
+fn parse_logical_and<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<LogicalAndExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -285,13 +632,16 @@ where
         .parse_next(input)
 }
 
-fn parse_basic_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_basic_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     alt((
         parse_not_parent_expr,
         parse_paren_expr,
+        parse_match_expr,
         parse_comp_expr.map(BasicExpr::Relation),
         parse_not_exist_expr,
         parse_exist_expr,
@@ -301,7 +651,9 @@ where
     .parse_next(input)
 }
 
-fn parse_paren_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_paren_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -310,7 +662,9 @@ where
         .parse_next(input)
 }
 
-fn parse_not_parent_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_not_parent_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -319,14 +673,18 @@ where
         .parse_next(input)
 }
 
-fn parse_paren_expr_inner<Registry>(input: &mut Input<Registry>) -> Result<LogicalOrExpr, Error>
+fn parse_paren_expr_inner<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<LogicalOrExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     delimited(text("("), parse_logical_or_expr, text(")")).parse_next(input)
 }
 
-fn parse_exist_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_exist_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -335,7 +693,9 @@ where
         .parse_next(input)
 }
 
-fn parse_not_exist_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_not_exist_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -344,14 +704,18 @@ where
         .parse_next(input)
 }
 
-fn parse_exist_expr_inner<Registry>(input: &mut Input<Registry>) -> Result<ExistExpr, Error>
+fn parse_exist_expr_inner<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ExistExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     parse_query.map(ExistExpr).parse_next(input)
 }
 
-fn parse_current_query<Registry>(input: &mut Input<Registry>) -> Result<Query, Error>
+fn parse_current_query<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Query<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -363,25 +727,52 @@ where
         .parse_next(input)
 }
 
-fn parse_query<Registry>(input: &mut Input<Registry>) -> Result<Query, Error>
+fn parse_parent_query<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Query<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    alt((parse_root_query, parse_current_query)).parse_next(input)
+    let (at, _) = extension_syntax(Extension::CurrentParent, (At, Caret)).parse_next(input)?;
+    ensure_extension(input, at, Extension::CurrentParent)?;
+    parse_path_segments
+        .map(|segments| Query {
+            kind: QueryKind::Parent,
+            segments,
+        })
+        .parse_next(input)
 }
 
-fn parse_comp_expr<Registry>(input: &mut Input<Registry>) -> Result<ComparisonExpr, Error>
+fn parse_query<Registry>(input: &mut Input<Registry>) -> Result<Query<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    let parse_second_comparable = move |i: &mut _| {
-        parse_comparable
-            .parse_next(i)
-            .map_err(|err| err.with_message("expected another comparable").cut())
+    alt((parse_root_query, parse_parent_query, parse_current_query)).parse_next(input)
+}
+
+fn parse_comp_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ComparisonExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let parse_second_comparable = move |i: &mut Input<Registry>| {
+        parse_arithmetic_expr.parse_next(i).map_err(|err: Error| {
+            if err.is_cut() {
+                return err;
+            }
+            let err = err.with_message("expected another comparable").cut();
+            match i.first() {
+                // JavaScript's strict (in)equality operators, e.g., `@.a === 1`
+                Some(token) if token.kind == Eq => err
+                    .with_help("JSONPath compares with `==` and `!=` rather than `===` and `!==`"),
+                _ => err,
+            }
+        })
     };
 
     (
-        parse_comparable,
+        parse_arithmetic_expr,
         parse_comparison_operator,
         parse_second_comparable,
     )
@@ -389,21 +780,282 @@ where
         .parse_next(input)
 }
 
-fn parse_comparable<Registry>(input: &mut Input<Registry>) -> Result<Comparable, Error>
+fn parse_match_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let match_operator = extension_syntax(Extension::RegexMatch, alt((Match, NotMatch)));
+    let (left, token) = (parse_comparable, match_operator).parse_next(input)?;
+    ensure_extension(input, token, Extension::RegexMatch)?;
+    lower_match_expr(input, left, token)
+}
+
+#[cfg(not(feature = "regex"))]
+fn lower_match_expr<Registry>(
+    _: &mut Input<Registry>,
+    _: Comparable<Registry::Value>,
+    token: &Token,
+) -> Result<BasicExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let message = "the regex match operator requires the `regex` feature";
+    Err(Error::new_cut(token.span, message))
+}
+
+/// Lower `left =~ /pattern/flags` onto the `search` function.
+#[cfg(feature = "regex")]
+fn lower_match_expr<Registry>(
+    input: &mut Input<Registry>,
+    left: Comparable<Registry::Value>,
+    token: &Token,
+) -> Result<BasicExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let regex = LiteralRegex
+        .try_map(parse_regex)
+        .parse_next(input)
+        .map_err(|err: Error| {
+            if err.is_cut() {
+                err
+            } else {
+                err.with_message("expected a regex literal, e.g., `/pattern/i`")
+                    .cut()
+            }
+        })?;
+
+    let left = match left {
+        Comparable::Literal(lit) => FunctionExprArg::Literal(lit),
+        Comparable::SingularQuery(query) => FunctionExprArg::SingularQuery(query),
+        Comparable::FunctionExpr(expr) => FunctionExprArg::FunctionExpr(expr),
+        Comparable::Parameter(name) => FunctionExprArg::Parameter(name),
+        Comparable::Binding(name) => FunctionExprArg::Binding(name),
+        Comparable::Key => FunctionExprArg::Key,
+    };
+    let pattern = FunctionExprArg::Literal(Literal::String(regex.to_pattern()));
+    let args = vec![left, pattern];
+    let function = Arc::new(crate::spec::function::builtin::search());
+    function.validate(args.as_slice()).map_err(|err| {
+        let message = err.to_string();
+        Error::new_cut(token.span, message).with_kind(ParseErrorKind::FunctionValidation(err))
+    })?;
+
+    Ok(BasicExpr::Match(MatchExpr {
+        regex,
+        negated: token.kind == NotMatch,
+        search: FunctionExpr {
+            name: function.name().to_string(),
+            args,
+            return_type: function.result_type(),
+            function,
+        },
+    }))
+}
+
+fn parse_arithmetic_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let first = parse_arithmetic_term(input)?;
+    let rest = repeat(0.., parse_additive_operation).parse_next(input)?;
+    Ok(fold_arithmetic(first, rest))
+}
+
+fn parse_additive_operation<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<(ArithmeticOperator, ArithmeticExpr<Registry::Value>), Error>
+where
+    Registry: FunctionRegistry,
+{
+    alt((
+        (parse_additive_operator, cut_err(parse_arithmetic_term)),
+        // `@.a -1` is lexed as an operand followed by a negative number
+        parse_negative_literal_term.map(|term| (ArithmeticOperator::Sub, term)),
+    ))
+    .parse_next(input)
+}
+
+fn parse_additive_operator<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticOperator, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let (op, token) = extension_syntax(
+        Extension::Arithmetic,
+        alt((
+            Plus.map(|t| (ArithmeticOperator::Add, t)),
+            Minus.map(|t| (ArithmeticOperator::Sub, t)),
+        )),
+    )
+    .parse_next(input)?;
+    ensure_extension(input, token, Extension::Arithmetic)?;
+    Ok(op)
+}
+
+fn parse_multiplicative_operator<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticOperator, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let (op, token) = extension_syntax(
+        Extension::Arithmetic,
+        alt((
+            Asterisk.map(|t| (ArithmeticOperator::Mul, t)),
+            Slash.map(|t| (ArithmeticOperator::Div, t)),
+            Percent.map(|t| (ArithmeticOperator::Rem, t)),
+        )),
+    )
+    .parse_next(input)?;
+    ensure_extension(input, token, Extension::Arithmetic)?;
+    Ok(op)
+}
+
+fn parse_arithmetic_term<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let first = parse_arithmetic_operand(input)?;
+    parse_multiplicative_operations(first, input)
+}
+
+fn parse_negative_literal_term<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let negative =
+        alt((LiteralInteger, LiteralFloat)).verify(|token: &Token| token.text().starts_with('-'));
+    let token = extension_syntax(Extension::Arithmetic, negative).parse_next(input)?;
+    ensure_extension(input, token, Extension::Arithmetic)?;
+    let extensions = input.state.extensions();
+    let literal = match parse_number(token, extensions)? {
+        Literal::Int(value) => Literal::Int(value.checked_neg().ok_or_else(|| {
+            Error::invalid_literal(token.span, "integer overflow in arithmetic expression")
+        })?),
+        Literal::Float(value) => Literal::Float(-value),
+        _ => unreachable!("numbers are either integers or floats"),
+    };
+    let first = ArithmeticExpr::Comparable(Comparable::Literal(literal));
+    parse_multiplicative_operations(first, input)
+}
+
+fn parse_multiplicative_operations<Registry>(
+    first: ArithmeticExpr<Registry::Value>,
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
-    backtrack_err(alt((
+    let rest = repeat(
+        0..,
+        (
+            parse_multiplicative_operator,
+            cut_err(parse_arithmetic_operand),
+        ),
+    )
+    .parse_next(input)?;
+    Ok(fold_arithmetic(first, rest))
+}
+
+fn parse_arithmetic_operand<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ArithmeticExpr<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    alt((
+        parse_comparable.map(ArithmeticExpr::Comparable),
+        delimited(text("("), parse_arithmetic_expr, text(")")),
+    ))
+    .parse_next(input)
+}
+
+fn fold_arithmetic<T: VariantValue>(
+    first: ArithmeticExpr<T>,
+    rest: Vec<(ArithmeticOperator, ArithmeticExpr<T>)>,
+) -> ArithmeticExpr<T> {
+    rest.into_iter()
+        .fold(first, |left, (op, right)| ArithmeticExpr::Binary {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        })
+}
+
+fn parse_comparable<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Comparable<Registry::Value>, Error>
+where
+    Registry: FunctionRegistry,
+{
+    alt((
         parse_literal_comparable,
-        parse_singular_path_comparable,
-        parse_function_expr_comparable,
-    )))
+        parse_current_key.map(|_| Comparable::Key),
+        backtrack_err(alt((
+            parse_singular_path_comparable,
+            parse_function_expr_comparable,
+        ))),
+        parse_parameter.map(Comparable::Parameter),
+        parse_binding_name.map(Comparable::Binding),
+    ))
     .parse_next(input)
 }
 
+fn parse_parameter<Registry>(input: &mut Input<Registry>) -> Result<String, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::Parameters, Parameter).parse_next(input)?;
+    ensure_extension(input, token, Extension::Parameters)?;
+    Ok(parameter_name(token).to_string())
+}
+
+fn parse_current_key<Registry>(input: &mut Input<Registry>) -> Result<(), Error>
+where
+    Registry: FunctionRegistry,
+{
+    // `@key` is written without whitespace, while `@ key` is not a valid query anyway
+    let current_key =
+        (At, text("key")).verify(|(at, key): &(&Token, &Token)| at.span.end == key.span.start);
+    let (at, _) = extension_syntax(Extension::CurrentKey, current_key).parse_next(input)?;
+    ensure_extension(input, at, Extension::CurrentKey)
+}
+
+fn parse_binding_name<Registry>(input: &mut Input<Registry>) -> Result<String, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::LetBindings, BindingName).parse_next(input)?;
+    ensure_extension(input, token, Extension::LetBindings)?;
+    let name = binding_name(token);
+    if !input.state.is_bound(name) {
+        let message = format!("binding `${name}` is not defined");
+        return Err(Error::new_cut(token.span, message));
+    }
+    Ok(name.to_string())
+}
+
+fn binding_name<'a>(token: &Token<'a>) -> &'a str {
+    token.text().trim_start_matches('$')
+}
+
+fn parameter_name<'a>(token: &Token<'a>) -> &'a str {
+    token.text().trim_start_matches('$')
+}
+
 fn parse_singular_path_comparable<Registry>(
     input: &mut Input<Registry>,
-) -> Result<Comparable, Error>
+) -> Result<Comparable<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -421,7 +1073,9 @@ where
         .parse_next(input)
 }
 
-fn parse_func_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_func_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -430,7 +1084,9 @@ where
         .parse_next(input)
 }
 
-fn parse_not_func_expr<Registry>(input: &mut Input<Registry>) -> Result<BasicExpr, Error>
+fn parse_not_func_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<BasicExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -439,18 +1095,20 @@ where
         .parse_next(input)
 }
 
-fn parse_func_expr_inner<Registry>(input: &mut Input<Registry>) -> Result<FunctionExpr, Error>
+fn parse_func_expr_inner<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<FunctionExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
     parse_function_expr
         .try_map(|expr| match expr.return_type {
             SPathType::Logical | SPathType::Nodes => Ok(expr),
-            SPathType::Value => Err({
+            received @ (SPathType::Value | SPathType::Predicate) => Err({
                 FunctionValidationError::IncorrectFunctionReturnType {
                     name: expr.name,
                     expected: vec![SPathType::Logical, SPathType::Nodes],
-                    received: SPathType::Value,
+                    received,
                 }
             }),
         })
@@ -459,7 +1117,7 @@ where
 
 fn parse_function_expr_comparable<Registry>(
     input: &mut Input<Registry>,
-) -> Result<Comparable, Error>
+) -> Result<Comparable<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -473,22 +1131,52 @@ where
                     received: SPathType::Logical,
                 }
             }),
-            SPathType::Nodes => Err({
+            received @ (SPathType::Nodes | SPathType::Predicate) => Err({
                 FunctionValidationError::IncorrectFunctionReturnType {
                     name: expr.name,
                     expected: vec![SPathType::Value],
-                    received: SPathType::Nodes,
+                    received,
                 }
             }),
         })
         .parse_next(input)
 }
 
-fn parse_function_expr<Registry>(input: &mut Input<Registry>) -> Result<FunctionExpr, Error>
+fn parse_function_expr<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<FunctionExpr<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
+    let (name, lparen) = peek((Identifier, text("("))).parse_next(input)?;
+    ensure_adjacent(input, name, lparen)?;
+    if !is_function_name(name.text()) {
+        ensure_extension(input, name, Extension::LenientFunctionNames).map_err(|err| {
+            let lowercase = name.text().to_lowercase();
+            if is_function_name(&lowercase) {
+                err.with_help(format!("did you mean `{lowercase}`?"))
+            } else {
+                err
+            }
+        })?;
+    }
+
     let registry = input.state.registry();
+    let Some(function) = registry.get(name.text()) else {
+        let undefined = FunctionValidationError::Undefined {
+            name: name.text().to_string(),
+        };
+        let message = undefined.to_string();
+        let err = Error::new_cut(name.span, message)
+            .with_kind(ParseErrorKind::FunctionValidation(undefined));
+        let names = registry.function_names();
+        return Err(
+            match did_you_mean(name.text(), names.iter().map(String::as_str)) {
+                Some(similar) => err.with_help(format!("did you mean `{similar}`?")),
+                None => err,
+            },
+        );
+    };
 
     (
         Identifier,
@@ -500,38 +1188,52 @@ where
     )
         .try_map(|(name, args)| {
             let name = name.text();
-            let args: Vec<FunctionExprArg> = args;
+            let args: Vec<FunctionExprArg<Registry::Value>> = args;
+            function.validate(args.as_slice())?;
 
-            let function = registry.get(name);
-            let function = function.ok_or_else(|| FunctionValidationError::Undefined {
-                name: name.to_string(),
-            })?;
-            function.validate(args.as_slice(), &registry)?;
-
-            Ok::<FunctionExpr, FunctionValidationError>(FunctionExpr {
+            Ok::<_, FunctionValidationError>(FunctionExpr {
                 name: name.to_string(),
                 args,
                 return_type: function.result_type(),
+                function: function.clone(),
             })
         })
         .parse_next(input)
 }
 
-fn parse_function_argument<Registry>(input: &mut Input<Registry>) -> Result<FunctionExprArg, Error>
+/// Whether the name is a function name of RFC 9535, e.g., `length` or `starts_with`.
+fn is_function_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn parse_function_argument<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<FunctionExprArg<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
+    // a literal, query or function argument must end the argument; otherwise, it's the
+    // beginning of a logical expression, e.g., `@.price > 10`.
+    let end_of_arg = || peek(alt((text(","), text(")"))));
+
     alt((
-        parse_literal.map(FunctionExprArg::Literal),
-        parse_singular_path.map(FunctionExprArg::SingularQuery),
-        parse_query.map(FunctionExprArg::FilterQuery),
-        parse_function_expr.map(FunctionExprArg::FunctionExpr),
+        terminated(parse_literal, end_of_arg()).map(FunctionExprArg::Literal),
+        terminated(parse_parameter, end_of_arg()).map(FunctionExprArg::Parameter),
+        terminated(parse_binding_name, end_of_arg()).map(FunctionExprArg::Binding),
+        terminated(parse_current_key, end_of_arg()).map(|_| FunctionExprArg::Key),
+        terminated(parse_singular_path, end_of_arg()).map(FunctionExprArg::SingularQuery),
+        terminated(parse_query, end_of_arg()).map(FunctionExprArg::FilterQuery),
+        terminated(parse_function_expr, end_of_arg()).map(FunctionExprArg::FunctionExpr),
         parse_logical_or_expr.map(FunctionExprArg::LogicalExpr),
     ))
     .parse_next(input)
 }
 
-fn parse_literal_comparable<Registry>(input: &mut Input<Registry>) -> Result<Comparable, Error>
+fn parse_literal_comparable<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<Comparable<Registry::Value>, Error>
 where
     Registry: FunctionRegistry,
 {
@@ -542,17 +1244,58 @@ fn parse_literal<Registry>(input: &mut Input<Registry>) -> Result<Literal, Error
 where
     Registry: FunctionRegistry,
 {
+    let extensions = input.state.extensions();
     alt((
-        LiteralString.try_map(parse_string).map(Literal::String),
-        LiteralInteger.try_map(parse_integer).map(Literal::Int),
-        LiteralFloat.try_map(parse_float).map(Literal::Float),
+        LiteralString
+            .try_map(move |token| parse_string(token, extensions))
+            .map(Literal::String),
+        LiteralInteger.try_map(move |token| parse_integer_literal(token, extensions)),
+        LiteralFloat
+            .try_map(move |token| parse_float(token, extensions))
+            .map(Literal::Float),
         TRUE.map(|_| Literal::Bool(true)),
         FALSE.map(|_| Literal::Bool(false)),
         NULL.map(|_| Literal::Null),
+        parse_array_literal,
+        parse_object_literal,
     ))
     .parse_next(input)
 }
 
+fn parse_array_literal<Registry>(input: &mut Input<Registry>) -> Result<Literal, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::CompositeLiterals, LBracket).parse_next(input)?;
+    ensure_extension(input, token, Extension::CompositeLiterals)?;
+    terminated(separated(0.., parse_literal, text(",")), RBracket)
+        .map(Literal::Array)
+        .parse_next(input)
+}
+
+fn parse_object_literal<Registry>(input: &mut Input<Registry>) -> Result<Literal, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let token = extension_syntax(Extension::CompositeLiterals, LBrace).parse_next(input)?;
+    ensure_extension(input, token, Extension::CompositeLiterals)?;
+    let extensions = input.state.extensions();
+    let member = separated_pair(
+        LiteralString.try_map(move |token| parse_string(token, extensions)),
+        text(":"),
+        parse_literal,
+    );
+    let members: Vec<(String, Literal)> =
+        terminated(separated(0.., member, text(",")), RBrace).parse_next(input)?;
+    for (i, (name, _)) in members.iter().enumerate() {
+        if members[..i].iter().any(|(n, _)| n == name) {
+            let message = format!("duplicate member name '{name}' in object literal");
+            return Err(Error::new_cut(token.span, message));
+        }
+    }
+    Ok(Literal::Object(members))
+}
+
 fn parse_comparison_operator<Registry>(
     input: &mut Input<Registry>,
 ) -> Result<ComparisonOperator, Error>
@@ -566,33 +1309,166 @@ where
         text("<").map(|_| ComparisonOperator::LessThan),
         text(">=").map(|_| ComparisonOperator::GreaterThanEqualTo),
         text(">").map(|_| ComparisonOperator::GreaterThan),
+        parse_alternative_operator,
+        parse_membership_operator,
     ))
     .parse_next(input)
 }
 
-fn parse_integer(token: &Token) -> Result<i64, Error> {
+fn parse_alternative_operator<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ComparisonOperator, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let (op, token) = extension_syntax(
+        Extension::AlternativeOperators,
+        alt((
+            text("=").map(|t| (ComparisonOperator::EqualTo, t)),
+            text("<>").map(|t| (ComparisonOperator::NotEqualTo, t)),
+        )),
+    )
+    .parse_next(input)?;
+    ensure_extension(input, token, Extension::AlternativeOperators).map_err(|err| {
+        let instead = match op {
+            ComparisonOperator::EqualTo => "==",
+            _ => "!=",
+        };
+        err.with_help(format!("did you mean `{instead}`?"))
+    })?;
+    Ok(op)
+}
+
+fn parse_membership_operator<Registry>(
+    input: &mut Input<Registry>,
+) -> Result<ComparisonOperator, Error>
+where
+    Registry: FunctionRegistry,
+{
+    let (op, token) = extension_syntax(
+        Extension::MembershipOperators,
+        alt((
+            text("in").map(|t| (ComparisonOperator::In, t)),
+            text("nin").map(|t| (ComparisonOperator::NotIn, t)),
+            text("contains").map(|t| (ComparisonOperator::Contains, t)),
+        )),
+    )
+    .parse_next(input)?;
+    ensure_extension(input, token, Extension::MembershipOperators)?;
+    Ok(op)
+}
+
+fn parse_integer(token: &Token, extensions: Extensions) -> Result<i64, Error> {
+    let text = integer_text(token, extensions)?;
+    text.parse()
+        .map_err(|err| Error::invalid_literal(token.span, format!("{err}")))
+}
+
+/// Parse an integer literal, which is a float if it's beyond the range of integers.
+fn parse_integer_literal(token: &Token, extensions: Extensions) -> Result<Literal, Error> {
+    let text = integer_text(token, extensions)?;
+    match text.parse() {
+        Ok(value) => Ok(Literal::Int(value)),
+        Err(_) => text
+            .parse()
+            .map(Literal::Float)
+            .map_err(|err| Error::invalid_literal(token.span, format!("{err}"))),
+    }
+}
+
+/// Parse an index or a slice bound, which RFC 9535 limits to ±(2^53-1) and never writes as `-0`.
+fn parse_index_integer(token: &Token, extensions: Extensions) -> Result<i64, Error> {
+    const MAX_INDEX: i64 = (1 << 53) - 1;
+
+    let value = parse_integer(token, extensions)?;
+    if !(-MAX_INDEX..=MAX_INDEX).contains(&value) || (value == 0 && token.text().starts_with('-')) {
+        check_extension(extensions, token.span, Extension::LenientIntegers)?;
+    }
+    Ok(value)
+}
+
+/// Parse an integer or float literal.
+fn parse_number(token: &Token, extensions: Extensions) -> Result<Literal, Error> {
+    match token.kind {
+        LiteralInteger => parse_integer_literal(token, extensions),
+        _ => parse_float(token, extensions).map(Literal::Float),
+    }
+}
+
+/// The text of an integer literal, with digit separators `_` removed.
+fn integer_text<'a>(token: &Token<'a>, extensions: Extensions) -> Result<Cow<'a, str>, Error> {
+    let mut text = Cow::Borrowed(token.text());
+    if text.contains('_') {
+        check_extension(extensions, token.span, Extension::DigitSeparators)?;
+        text = Cow::Owned(text.replace('_', ""));
+    }
+    ensure_no_leading_zeros(token, &text, extensions)?;
+    Ok(text)
+}
+
+fn ensure_no_leading_zeros(token: &Token, text: &str, extensions: Extensions) -> Result<(), Error> {
+    let digits = text.trim_start_matches('-');
+    let int = digits.split(['.', 'e', 'E']).next().unwrap_or(digits);
+    if int.len() > 1 && int.starts_with('0') {
+        check_extension(extensions, token.span, Extension::LenientIntegers)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "regex")]
+fn parse_regex(token: &Token) -> Result<RegexLiteral, Error> {
+    let text = token.text();
+    let (pattern, flags) = text[1..]
+        .rsplit_once('/')
+        .expect("regex literal ends with '/'");
+    if let Some(flag) = flags.chars().find(|c| !matches!(c, 'i' | 'm' | 's')) {
+        let message = format!("unknown regex flag '{flag}', expected any of 'i', 'm' and 's'");
+        return Err(Error::invalid_literal(token.span, message));
+    }
+
+    let regex = RegexLiteral {
+        pattern: pattern.to_string(),
+        flags: flags.to_string(),
+    };
+    // the same as the `search` function
+    if let Err(err) = regex::Regex::new(format!("(?R)({})", regex.to_pattern()).as_str()) {
+        // syntax errors render the pattern over multiple lines; keep the reason only
+        let err = err.to_string();
+        let reason = err.lines().last().unwrap_or_default();
+        let reason = reason.trim_start_matches("error: ");
+        return Err(Error::invalid_literal(
+            token.span,
+            format!("invalid regex: {reason}"),
+        ));
+    }
+    Ok(regex)
+}
+
+fn parse_depth(token: &Token) -> Result<usize, Error> {
     let text = token.text();
     text.parse()
-        .map_err(|err| Error::new_cut(token.span, format!("{err}")))
+        .map_err(|err| Error::invalid_literal(token.span, format!("invalid depth: {err}")))
 }
 
-fn parse_float(token: &Token) -> Result<f64, Error> {
+fn parse_float(token: &Token, extensions: Extensions) -> Result<f64, Error> {
     let text = token.text();
+    ensure_no_leading_zeros(token, text, extensions)?;
     text.parse()
-        .map_err(|err| Error::new_cut(token.span, format!("{err}")))
+        .map_err(|err| Error::invalid_literal(token.span, format!("{err}")))
 }
 
-fn parse_string(token: &Token) -> Result<String, Error> {
+fn parse_string(token: &Token, extensions: Extensions) -> Result<String, Error> {
     let text = token.text();
     let mut chars = text.chars();
 
     let quote = chars.next().expect("quote char always exist");
     if chars.next_back() != Some(quote) {
-        return Err(Error::new_cut(token.span, "mismatched quote"));
+        return Err(Error::invalid_literal(token.span, "mismatched quote"));
     }
 
     let mut chars = chars.peekable();
     let mut output = String::new();
+    let lenient = || check_extension(extensions, token.span, Extension::LenientStrings);
 
     while let Some(c) = chars.next() {
         if c == '\\' {
@@ -602,22 +1478,38 @@ fn parse_string(token: &Token) -> Result<String, Error> {
                 Some('n') => output.push('\n'),
                 Some('r') => output.push('\r'),
                 Some('t') => output.push('\t'),
+                Some('/') => output.push('/'),
                 Some('\\') => output.push('\\'),
-                Some('u') => output.push(
-                    unescape_unicode(&mut chars)
-                        .ok_or_else(|| Error::new_cut(token.span, "invalid escape sequence"))?,
-                ),
-                Some('x') => output.push(
-                    unescape_byte(&mut chars)
-                        .ok_or_else(|| Error::new_cut(token.span, "invalid escape sequence"))?,
-                ),
-                Some(c) if c.is_digit(8) => output.push(unescape_octal(c, &mut chars)),
+                Some('u') => output.push(unescape_unicode(&mut chars).ok_or_else(|| {
+                    Error::invalid_literal(token.span, "invalid escape sequence")
+                })?),
+                Some('x') => {
+                    lenient()?;
+                    output.push(unescape_byte(&mut chars).ok_or_else(|| {
+                        Error::invalid_literal(token.span, "invalid escape sequence")
+                    })?)
+                }
+                Some(c) if c.is_digit(8) => {
+                    lenient()?;
+                    output.push(unescape_octal(c, &mut chars))
+                }
                 Some(c) if c == quote => output.push(quote),
-                _ => return Err(Error::new_cut(token.span, "invalid escape sequence")),
+                _ => {
+                    return Err(Error::invalid_literal(
+                        token.span,
+                        "invalid escape sequence",
+                    ))
+                }
             };
         } else if c == quote {
-            return Err(Error::new_cut(token.span, "intermediately close quote"));
+            return Err(Error::invalid_literal(
+                token.span,
+                "intermediately close quote",
+            ));
         } else {
+            if c.is_ascii_control() && c != '\x7F' {
+                lenient()?;
+            }
             output.push(c);
         }
     }
@@ -625,14 +1517,30 @@ fn parse_string(token: &Token) -> Result<String, Error> {
     Ok(output)
 }
 
+/// Unescape `\uXXXX`, or a surrogate pair `\uD83D\uDE00`.
 fn unescape_unicode(chars: &mut Peekable<impl Iterator<Item = char>>) -> Option<char> {
-    let mut code = 0;
-
-    for c in chars.take(4) {
-        code = code * 16 + c.to_digit(16)?;
+    fn hex4(chars: &mut impl Iterator<Item = char>) -> Option<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            code = code * 16 + chars.next()?.to_digit(16)?;
+        }
+        Some(code)
     }
 
-    char::from_u32(code)
+    let code = hex4(chars)?;
+    match code {
+        0xD800..=0xDBFF => {
+            if chars.next()? != '\\' || chars.next()? != 'u' {
+                return None;
+            }
+            let low = hex4(chars)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return None;
+            }
+            char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00))
+        }
+        code => char::from_u32(code),
+    }
 }
 
 fn unescape_byte(chars: &mut Peekable<impl Iterator<Item = char>>) -> Option<char> {
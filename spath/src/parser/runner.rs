@@ -22,22 +22,27 @@ use crate::parser::parse::parse_query_main;
 use crate::parser::token::Token;
 use crate::parser::token::Tokenizer;
 use crate::spec::function::FunctionRegistry;
-use crate::spec::query::Query;
+use crate::spec::query::MainQuery;
+use crate::Extensions;
 use crate::ParseError;
 use crate::VariantValue;
 
-pub fn run_tokenizer(source: &str) -> Result<Vec<Token>, Error> {
+pub fn run_tokenizer(source: &str) -> Result<Vec<Token<'_>>, Error> {
     Tokenizer::new(source).collect::<Result<_, _>>()
 }
 
-pub fn run_parser<T, Registry>(source: &str, registry: Arc<Registry>) -> Result<Query, ParseError>
+pub fn run_parser<T, Registry>(
+    source: &str,
+    registry: Arc<Registry>,
+    extensions: Extensions,
+) -> Result<MainQuery<T>, ParseError>
 where
     T: VariantValue,
     Registry: FunctionRegistry<Value = T>,
 {
     let tokens = run_tokenizer(source).map_err(|err| err.into_parse_error(source))?;
     let input = TokenSlice::new(&tokens);
-    let state = InputState::new(registry);
+    let state = InputState::new(registry, extensions);
     let mut input = Input { input, state };
     parse_query_main(&mut input).map_err(|err| err.into_parse_error(source))
 }
@@ -19,6 +19,7 @@ use logos::Logos;
 
 use crate::parser::error::Error;
 use crate::parser::range::Range;
+use crate::ParseErrorKind;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Token<'a> {
@@ -51,6 +52,8 @@ pub struct Tokenizer<'a> {
     source: &'a str,
     lexer: Lexer<'a, TokenKind>,
     eoi: bool,
+    /// Whether a regex literal may follow, i.e., right after `=~` or `!~`.
+    regex: bool,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -59,25 +62,77 @@ impl<'a> Tokenizer<'a> {
             source,
             lexer: TokenKind::lexer(source),
             eoi: false,
+            regex: false,
         }
     }
+
+    /// Lex a `/pattern/flags` regex literal if it's the next token.
+    ///
+    /// A regex literal is only recognized after a match operator; otherwise, `/` is a division.
+    fn next_regex(&mut self) -> Option<Result<Token<'a>, Error>> {
+        let remainder = self.lexer.remainder();
+        let rest = remainder.trim_start_matches([' ', '\t', '\r', '\n', '\x0C']);
+        let pattern = rest.strip_prefix('/')?;
+        let skipped = remainder.len() - rest.len();
+        let start = self.lexer.span().end + skipped;
+
+        let mut escaped = false;
+        let Some(end) = pattern.find(|c| match c {
+            _ if escaped => {
+                escaped = false;
+                false
+            }
+            '\\' => {
+                escaped = true;
+                false
+            }
+            c => c == '/',
+        }) else {
+            self.lexer.bump(remainder.len());
+            let span = Range::from(start..self.source.len());
+            return Some(Err(Error::new_cut(span, "unterminated regex literal")
+                .with_kind(ParseErrorKind::Lexing)));
+        };
+        let flags = pattern[end + 1..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(pattern.len() - end - 1);
+
+        let len = 1 + end + 1 + flags;
+        self.lexer.bump(skipped + len);
+        Some(Ok(Token {
+            source: self.source,
+            kind: TokenKind::LiteralRegex,
+            span: Range::from(start..start + len),
+        }))
+    }
 }
 
 impl<'a> Iterator for Tokenizer<'a> {
     type Item = Result<Token<'a>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if std::mem::take(&mut self.regex) {
+            if let Some(token) = self.next_regex() {
+                return Some(token);
+            }
+        }
+
         match self.lexer.next() {
             Some(Err(_)) => {
                 let span = Range::from(self.lexer.span().start..self.source.len());
                 let message = "failed to recognize the rest tokens";
-                Some(Err(Error::new_cut(span, message)))
+                Some(Err(
+                    Error::new_cut(span, message).with_kind(ParseErrorKind::Lexing)
+                ))
+            }
+            Some(Ok(kind)) => {
+                self.regex = matches!(kind, TokenKind::Match | TokenKind::NotMatch);
+                Some(Ok(Token {
+                    source: self.source,
+                    kind,
+                    span: self.lexer.span().into(),
+                }))
             }
-            Some(Ok(kind)) => Some(Ok(Token {
-                source: self.source,
-                kind,
-                span: self.lexer.span().into(),
-            })),
             None => {
                 if !self.eoi {
                     self.eoi = true;
@@ -101,6 +156,12 @@ pub enum TokenKind {
     #[regex(r#"[_a-zA-Z\u0080-\uFFFF][_a-zA-Z0-9\u0080-\uFFFF]*"#)]
     Identifier,
 
+    #[regex(r"\$\$[_a-zA-Z][_a-zA-Z0-9]*")]
+    Parameter,
+
+    #[regex(r"\$[_a-zA-Z][_a-zA-Z0-9]*")]
+    BindingName,
+
     #[regex(r#"'([^'\\]|\\.)*'"#)]
     #[regex(r#""([^"\\]|\\.)*""#)]
     LiteralString,
@@ -112,6 +173,9 @@ pub enum TokenKind {
     #[regex(r"(-)?[0-9]+\.[0-9]+([eE][+-]?[0-9]+)?")]
     LiteralFloat,
 
+    /// A `/pattern/flags` regex literal, lexed by [`Tokenizer`] after `=~` or `!~`.
+    LiteralRegex,
+
     // Symbols
     #[token("=")]
     #[token("==")]
@@ -121,6 +185,12 @@ pub enum TokenKind {
     NotEq,
     #[token("!")]
     Not,
+    #[token("=~")]
+    Match,
+    #[token("=>")]
+    Arrow,
+    #[token("!~")]
+    NotMatch,
     #[token("<")]
     Lt,
     #[token(">")]
@@ -133,6 +203,8 @@ pub enum TokenKind {
     And,
     #[token("||")]
     Or,
+    #[token("|")]
+    Pipe,
     #[token("$")]
     Dollar,
     #[token("@")]
@@ -143,10 +215,20 @@ pub enum TokenKind {
     DoubleDot,
     #[token("*")]
     Asterisk,
+    #[token("+")]
+    Plus,
+    #[token("-")]
+    Minus,
+    #[token("/")]
+    Slash,
+    #[token("%")]
+    Percent,
     #[token(":")]
     Colon,
     #[token(",")]
     Comma,
+    #[token(";")]
+    Semicolon,
     #[token("?")]
     QuestionMark,
     #[token("(")]
@@ -157,6 +239,14 @@ pub enum TokenKind {
     LBracket,
     #[token("]")]
     RBracket,
+    #[token("{")]
+    LBrace,
+    #[token("}")]
+    RBrace,
+    #[token("~")]
+    Tilde,
+    #[token("^")]
+    Caret,
 
     // §2.3.5.1. Syntax
     // true, false, and null are lowercase only (case-sensitive).
@@ -172,4 +262,56 @@ impl TokenKind {
     pub fn is_eoi(&self) -> bool {
         matches!(self, TokenKind::EOI)
     }
+
+    /// Describe the token as an expectation in error messages.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            TokenKind::EOI => "end of input",
+            TokenKind::Whitespace => "whitespace",
+            TokenKind::Identifier => "identifier",
+            TokenKind::Parameter => "parameter",
+            TokenKind::BindingName => "binding name",
+            TokenKind::LiteralString => "string literal",
+            TokenKind::LiteralInteger => "integer literal",
+            TokenKind::LiteralFloat => "number literal",
+            TokenKind::LiteralRegex => "regex literal",
+            TokenKind::Eq => "`==`",
+            TokenKind::NotEq => "`!=`",
+            TokenKind::Not => "`!`",
+            TokenKind::Match => "`=~`",
+            TokenKind::Arrow => "`=>`",
+            TokenKind::NotMatch => "`!~`",
+            TokenKind::Lt => "`<`",
+            TokenKind::Gt => "`>`",
+            TokenKind::Lte => "`<=`",
+            TokenKind::Gte => "`>=`",
+            TokenKind::And => "`&&`",
+            TokenKind::Or => "`||`",
+            TokenKind::Pipe => "`|`",
+            TokenKind::Dollar => "`$`",
+            TokenKind::At => "`@`",
+            TokenKind::Dot => "`.`",
+            TokenKind::DoubleDot => "`..`",
+            TokenKind::Asterisk => "`*`",
+            TokenKind::Plus => "`+`",
+            TokenKind::Minus => "`-`",
+            TokenKind::Slash => "`/`",
+            TokenKind::Percent => "`%`",
+            TokenKind::Colon => "`:`",
+            TokenKind::Comma => "`,`",
+            TokenKind::Semicolon => "`;`",
+            TokenKind::QuestionMark => "`?`",
+            TokenKind::LParen => "`(`",
+            TokenKind::RParen => "`)`",
+            TokenKind::LBracket => "`[`",
+            TokenKind::RBracket => "`]`",
+            TokenKind::LBrace => "`{`",
+            TokenKind::RBrace => "`}`",
+            TokenKind::Tilde => "`~`",
+            TokenKind::Caret => "`^`",
+            TokenKind::FALSE => "`false`",
+            TokenKind::NULL => "`null`",
+            TokenKind::TRUE => "`true`",
+        }
+    }
 }
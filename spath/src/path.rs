@@ -21,6 +21,11 @@ use std::fmt;
 use std::slice::Iter;
 use std::slice::SliceIndex;
 
+use crate::ConcreteVariantArray;
+use crate::ConcreteVariantObject;
+use crate::Literal;
+use crate::VariantValue;
+
 #[derive(Debug, Default, Eq, PartialEq, Clone, PartialOrd, Ord)]
 pub struct NormalizedPath<'a>(Vec<PathElement<'a>>);
 
@@ -29,6 +34,18 @@ impl<'a> NormalizedPath<'a> {
         self.0.push(elem.into())
     }
 
+    pub(crate) fn pop(&mut self) -> Option<PathElement<'a>> {
+        self.0.pop()
+    }
+
+    /// Resolve the node at this path from the `root` node.
+    pub(crate) fn resolve<T: VariantValue>(&self, root: &'a T) -> Option<&'a T> {
+        self.0.iter().try_fold(root, |node, elem| match elem {
+            PathElement::Name(name) => node.as_object()?.get(name),
+            PathElement::Index(index) => node.as_array()?.get(*index),
+        })
+    }
+
     pub(crate) fn clone_and_push<T: Into<PathElement<'a>>>(&self, elem: T) -> Self {
         let mut new_path = self.clone();
         new_path.push(elem.into());
@@ -93,8 +110,8 @@ impl fmt::Display for NormalizedPath<'_> {
         write!(f, "$")?;
         for elem in &self.0 {
             match elem {
-                PathElement::Name(name) => write!(f, "['{name}']")?,
-                PathElement::Index(index) => write!(f, "[{index}]")?,
+                PathElement::Name(_) => write!(f, "['{elem}']")?,
+                PathElement::Index(_) => write!(f, "[{elem}]")?,
             }
         }
         Ok(())
@@ -102,7 +119,7 @@ impl fmt::Display for NormalizedPath<'_> {
 }
 
 /// An element within a [`NormalizedPath`]
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum PathElement<'a> {
     /// A key within an object
     Name(&'a str),
@@ -136,6 +153,14 @@ impl PathElement<'_> {
     pub fn is_index(&self) -> bool {
         self.as_index().is_some()
     }
+
+    /// Convert the [`PathElement`] to a string or an integer literal.
+    pub(crate) fn to_literal(self) -> Literal {
+        match self {
+            PathElement::Name(name) => Literal::String(name.to_string()),
+            PathElement::Index(index) => Literal::Int(index as i64),
+        }
+    }
 }
 
 impl<'a> From<&'a String> for PathElement<'a> {
@@ -207,10 +232,10 @@ impl fmt::Display for PathElement<'_> {
                         '\u{000D}' => write!(f, r#"\r"#)?, // r CR carriage return
                         '\u{0009}' => write!(f, r#"\t"#)?, // t HT horizontal tab
                         '\u{0027}' => write!(f, r#"\'"#)?, // ' apostrophe
-                        '\u{005C}' => write!(f, r#"\"#)?,  // \ backslash (reverse solidus)
-                        ('\x00'..='\x07') | '\x0b' | '\x0e' | '\x0f' => {
-                            // "00"-"07", "0b", "0e"-"0f"
-                            write!(f, "\\u000{:x}", c as i32)?
+                        '\u{005C}' => write!(f, r#"\\"#)?, // \ backslash (reverse solidus)
+                        ('\x00'..='\x07') | '\x0b' | ('\x0e'..='\x1f') => {
+                            // "00"-"07", "0b", "0e"-"1f"
+                            write!(f, "\\u{:04x}", c as u32)?
                         }
                         _ => write!(f, "{c}")?,
                     }
@@ -226,6 +251,7 @@ impl fmt::Display for PathElement<'_> {
 mod tests {
     use insta::assert_snapshot;
 
+    use super::NormalizedPath;
     use super::PathElement;
 
     #[test]
@@ -237,7 +263,7 @@ mod tests {
         // escape_apostrophes
         assert_snapshot!(PathElement::Name("'hi'"), @r#"\'hi\'"#);
         // escapes
-        assert_snapshot!(PathElement::Name(r#"'\b\f\n\r\t\\'"#), @r#"\'\b\f\n\r\t\\\'"#);
+        assert_snapshot!(PathElement::Name(r#"'\b\f\n\r\t\\'"#), @r#"\'\\b\\f\\n\\r\\t\\\\\'"#);
         // escape_vertical_unicode
         assert_snapshot!(PathElement::Name("\u{000B}"), @r#"\u000b"#);
         // escape_unicode_null
@@ -246,5 +272,15 @@ mod tests {
         assert_snapshot!(PathElement::Name(
             "\u{0001}\u{0002}\u{0003}\u{0004}\u{0005}\u{0006}\u{0007}\u{000e}\u{000F}"
         ), @r#"\u0001\u0002\u0003\u0004\u0005\u0006\u0007\u000e\u000f"#);
+        assert_snapshot!(PathElement::Name("\u{0010}\u{001F}"), @r#"\u0010\u001f"#);
+    }
+
+    #[test]
+    fn test_normalized_path() {
+        let mut path = NormalizedPath::default();
+        path.push(PathElement::Name("a"));
+        path.push(1);
+        path.push(PathElement::Name("it's \\ \u{001F}"));
+        assert_snapshot!(path, @r#"$['a'][1]['it\'s \\ \u001f']"#);
     }
 }
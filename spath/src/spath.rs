@@ -17,41 +17,232 @@ use std::sync::Arc;
 
 use crate::parser::run_parser;
 use crate::spec::function::FunctionRegistry;
-use crate::spec::query::Query;
-use crate::spec::query::Queryable;
+use crate::spec::query::EvalContext;
+use crate::spec::query::MainQuery;
+use crate::Extensions;
 use crate::LocatedNodeList;
+use crate::Node;
 use crate::NodeList;
+use crate::OwnedNodeList;
+use crate::Params;
 use crate::ParseError;
+use crate::UnboundParamError;
+use crate::VariantBuilder;
 use crate::VariantValue;
 
 #[derive(Debug, Clone)]
-pub struct SPath<T: VariantValue, Registry: FunctionRegistry<Value = T>> {
-    query: Query,
-    registry: Arc<Registry>,
+pub struct SPath<T: VariantValue> {
+    query: MainQuery<T>,
 }
 
-impl<T: VariantValue, Registry: FunctionRegistry<Value = T>> SPath<T, Registry> {
-    pub fn parse_with_registry(query: &str, registry: Registry) -> Result<Self, ParseError> {
-        let registry = Arc::new(registry);
-        let query = run_parser(query, registry.clone())?;
-        Ok(Self { query, registry })
+impl<T: VariantValue> SPath<T> {
+    /// Parse an SPath query in the strict RFC 9535 mode, resolving functions with the given
+    /// registry.
+    ///
+    /// Functions are resolved once at parse time, so the registry is not retained.
+    pub fn parse_with_registry<Registry>(
+        query: &str,
+        registry: Registry,
+    ) -> Result<Self, ParseError>
+    where
+        Registry: FunctionRegistry<Value = T>,
+    {
+        Self::parse_with_extensions(query, registry, Extensions::none())
     }
 
-    pub fn query<'b>(&self, value: &'b T) -> NodeList<'b, T> {
-        let nodes = self.query.query(value, value, &self.registry);
-        NodeList::new(nodes)
+    /// Parse an SPath query, resolving functions with the given registry and accepting the
+    /// given syntax extensions beyond RFC 9535.
+    pub fn parse_with_extensions<Registry>(
+        query: &str,
+        registry: Registry,
+        extensions: Extensions,
+    ) -> Result<Self, ParseError>
+    where
+        Registry: FunctionRegistry<Value = T>,
+    {
+        let query = run_parser(query, Arc::new(registry), extensions)?;
+        Ok(Self { query })
     }
 
-    pub fn query_located<'b>(&self, value: &'b T) -> LocatedNodeList<'b, T> {
-        let nodes = self
-            .query
-            .query_located(value, value, &self.registry, Default::default());
-        LocatedNodeList::new(nodes)
+    /// Whether the query only yields nodes borrowed from the queried value.
+    ///
+    /// A query is not borrowed if any of its union branches selects member names with `~`, or
+    /// constructs objects with a projection `.{...}`. [`SPath::query`] and
+    /// [`SPath::query_located`] return an error for such queries; use [`SPath::query_nodes`] or
+    /// [`SPath::query_projected`] instead.
+    pub fn is_borrowed(&self) -> bool {
+        self.query.is_borrowed()
     }
+
+    /// Run the query over the value, yielding the nodes borrowed from the value.
+    ///
+    /// Returns an error if the query is not borrowed, see [`SPath::is_borrowed`], or if it
+    /// references any parameter; use [`SPath::query_with`] to bind them.
+    pub fn query<'b>(&self, value: &'b T) -> Result<NodeList<'b, T>, QueryError> {
+        self.check_borrowed()?;
+        self.check_params(&Params::new())?;
+        Ok(NodeList::new(self.query.query(EvalContext::new(value))))
+    }
+
+    /// Run the query over the value, yielding the located nodes borrowed from the value.
+    ///
+    /// Returns an error if the query is not borrowed, as the nodes synthesized by extensions have
+    /// no location in the value, see [`SPath::is_borrowed`], or if the query references any
+    /// parameter; use [`SPath::query_located_with`] to bind them.
+    pub fn query_located<'b>(&self, value: &'b T) -> Result<LocatedNodeList<'b, T>, QueryError> {
+        self.check_borrowed()?;
+        self.check_params(&Params::new())?;
+        Ok(LocatedNodeList::new(
+            self.query.query_located(EvalContext::new(value)),
+        ))
+    }
+
+    /// Run the query over the value, yielding both nodes borrowed from the value and nodes
+    /// synthesized by extensions.
+    ///
+    /// Returns an error if the query constructs objects with a projection; use
+    /// [`SPath::query_projected`] to retrieve them. Returns an error if the query references any
+    /// parameter; use [`SPath::query_nodes_with`] to bind them.
+    pub fn query_nodes<'b>(&self, value: &'b T) -> Result<Vec<Node<'b, T>>, QueryError> {
+        self.check_unprojected()?;
+        self.check_params(&Params::new())?;
+        Ok(self.query.query_nodes(EvalContext::new(value)))
+    }
+
+    /// Run the query over the value, yielding owned values, including the objects constructed by
+    /// a projection `.{...}`.
+    ///
+    /// Nodes borrowed from the value are cloned. Returns an error if the query references any
+    /// parameter; use [`SPath::query_projected_with`] to bind them.
+    pub fn query_projected(&self, value: &T) -> Result<OwnedNodeList<T>, QueryError>
+    where
+        T: VariantBuilder,
+    {
+        self.check_params(&Params::new())?;
+        Ok(OwnedNodeList::new(
+            self.query.query_projected(EvalContext::new(value)),
+        ))
+    }
+
+    /// Run the query over the value with the given parameters bound, like [`SPath::query`].
+    ///
+    /// Returns an error if any parameter referenced by the query is not bound, besides the errors
+    /// of the method without parameters.
+    pub fn query_with<'b>(
+        &self,
+        value: &'b T,
+        params: &'b Params<T>,
+    ) -> Result<NodeList<'b, T>, QueryError> {
+        self.check_borrowed()?;
+        let ctx = self.bind_params(value, params)?;
+        Ok(NodeList::new(self.query.query(ctx)))
+    }
+
+    /// Run the query over the value with the given parameters bound, like
+    /// [`SPath::query_located`].
+    ///
+    /// Returns an error if any parameter referenced by the query is not bound, besides the errors
+    /// of the method without parameters.
+    pub fn query_located_with<'b>(
+        &self,
+        value: &'b T,
+        params: &'b Params<T>,
+    ) -> Result<LocatedNodeList<'b, T>, QueryError> {
+        self.check_borrowed()?;
+        let ctx = self.bind_params(value, params)?;
+        Ok(LocatedNodeList::new(self.query.query_located(ctx)))
+    }
+
+    /// Run the query over the value with the given parameters bound, like
+    /// [`SPath::query_nodes`].
+    ///
+    /// Returns an error if any parameter referenced by the query is not bound, besides the errors
+    /// of the method without parameters.
+    pub fn query_nodes_with<'b>(
+        &self,
+        value: &'b T,
+        params: &'b Params<T>,
+    ) -> Result<Vec<Node<'b, T>>, QueryError> {
+        self.check_unprojected()?;
+        let ctx = self.bind_params(value, params)?;
+        Ok(self.query.query_nodes(ctx))
+    }
+
+    /// Run the query over the value with the given parameters bound, like
+    /// [`SPath::query_projected`].
+    ///
+    /// Returns an error if any parameter referenced by the query is not bound.
+    pub fn query_projected_with(
+        &self,
+        value: &T,
+        params: &Params<T>,
+    ) -> Result<OwnedNodeList<T>, QueryError>
+    where
+        T: VariantBuilder,
+    {
+        let ctx = self.bind_params(value, params)?;
+        Ok(OwnedNodeList::new(self.query.query_projected(ctx)))
+    }
+
+    fn check_borrowed(&self) -> Result<(), QueryError> {
+        match self.query.is_borrowed() {
+            true => Ok(()),
+            false => Err(QueryError::NotBorrowed),
+        }
+    }
+
+    fn check_unprojected(&self) -> Result<(), QueryError> {
+        match self.query.branches.iter().any(|b| b.projection.is_some()) {
+            true => Err(QueryError::Projected),
+            false => Ok(()),
+        }
+    }
+
+    fn bind_params<'b>(
+        &self,
+        value: &'b T,
+        params: &'b Params<T>,
+    ) -> Result<EvalContext<'_, 'b, T>, UnboundParamError> {
+        self.check_params(params)?;
+        Ok(EvalContext::new(value).with_params(params))
+    }
+
+    fn check_params(&self, params: &Params<T>) -> Result<(), UnboundParamError> {
+        match self.query.params.iter().find(|name| !params.contains(name)) {
+            Some(name) => Err(UnboundParamError(name.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An error that an SPath query cannot be run with the given method or parameters.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum QueryError {
+    /// A parameter referenced by the query is not bound.
+    #[error(transparent)]
+    UnboundParam(#[from] UnboundParamError),
+    /// The query yields nodes not borrowed from the queried value, e.g., the member names
+    /// selected by `~`.
+    #[error("query yields nodes not borrowed from the value; use `query_nodes` or `query_projected` instead")]
+    NotBorrowed,
+    /// The query constructs objects with a projection `.{...}`.
+    #[error("query constructs objects with a projection; use `query_projected` instead")]
+    Projected,
 }
 
-impl<T: VariantValue, Registry: FunctionRegistry<Value = T>> fmt::Display for SPath<T, Registry> {
+impl<T: VariantValue> fmt::Display for SPath<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.query)
     }
 }
+
+// A parsed SPath can be shared across threads whenever the variant value can.
+const _: () = {
+    const fn assert_send_sync<S: Send + Sync>() {}
+
+    #[allow(dead_code)]
+    const fn assert_spath_send_sync<T: VariantValue + Send + Sync>() {
+        assert_send_sync::<SPath<T>>();
+    }
+};
@@ -0,0 +1,177 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aggregate functions over node lists, enabled by the `aggregate-functions` feature.
+//!
+//! Each function takes a nodes argument, e.g., `sum(@.items[*].price)`. Nodes that are arrays are
+//! flattened by one level, so that `sum(@.prices)` aggregates the members of the `prices` array.
+//!
+//! Numeric aggregates ignore non-numeric members. Integers and floats keep their kinds as in math
+//! functions, e.g., the average of `[1, 3]` is `2` while the average of `[1, 2]` is `1.5`.
+
+use super::unpack_args;
+use crate::number::Number;
+use crate::spec::function::Function;
+use crate::spec::function::NodesType;
+use crate::spec::function::PredicateType;
+use crate::spec::function::SPathType;
+use crate::spec::function::SPathValue;
+use crate::ConcreteVariantArray;
+use crate::Literal;
+use crate::VariantValue;
+
+/// `sum(nodes)` returns the sum of the numbers; `0` if there are no numbers.
+pub fn sum<T: VariantValue>() -> Function<T> {
+    numeric_function("sum", |numbers| {
+        numbers
+            .into_iter()
+            .try_fold(Number::Int(0), |acc, n| acc.add(n))
+    })
+}
+
+/// `avg(nodes)` returns the average of the numbers; nothing if there are no numbers.
+pub fn avg<T: VariantValue>() -> Function<T> {
+    numeric_function("avg", |numbers| {
+        let count = Number::Int(i64::try_from(numbers.len()).ok()?);
+        let sum = numbers
+            .into_iter()
+            .try_fold(Number::Int(0), |acc, n| acc.add(n))?;
+        sum.div(count)
+    })
+}
+
+/// `min(nodes)` returns the minimum of the numbers; nothing if there are no numbers.
+pub fn min<T: VariantValue>() -> Function<T> {
+    numeric_function("min", |numbers| {
+        numbers
+            .into_iter()
+            .reduce(|acc, n| match n.partial_cmp(acc) {
+                Some(std::cmp::Ordering::Less) => n,
+                _ => acc,
+            })
+    })
+}
+
+/// `max(nodes)` returns the maximum of the numbers; nothing if there are no numbers.
+pub fn max<T: VariantValue>() -> Function<T> {
+    numeric_function("max", |numbers| {
+        numbers
+            .into_iter()
+            .reduce(|acc, n| match n.partial_cmp(acc) {
+                Some(std::cmp::Ordering::Greater) => n,
+                _ => acc,
+            })
+    })
+}
+
+/// `distinct_count(nodes)` returns the number of distinct values of any kind.
+pub fn distinct_count<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "distinct_count",
+        vec![SPathType::Nodes],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([nodes]): Option<[NodesType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+            let mut distinct: Vec<&T> = vec![];
+            for member in members(nodes) {
+                if !distinct.iter().any(|v| v.is_equal_to(member)) {
+                    distinct.push(member);
+                }
+            }
+
+            match i64::try_from(distinct.len())
+                .ok()
+                .and_then(|len| T::from_literal(Literal::Int(len)))
+            {
+                Some(v) => SPathValue::Value(v),
+                None => SPathValue::Nothing,
+            }
+        }),
+    )
+}
+
+/// `any(nodes, predicate)` tests whether the predicate holds for any of the members.
+pub fn any<T: VariantValue>() -> Function<T> {
+    predicate_function("any", false)
+}
+
+/// `all(nodes, predicate)` tests whether the predicate holds for all the members; true if there
+/// are no members.
+pub fn all<T: VariantValue>() -> Function<T> {
+    predicate_function("all", true)
+}
+
+fn numeric_function<T: VariantValue>(
+    name: &'static str,
+    op: fn(Vec<Number>) -> Option<Number>,
+) -> Function<T> {
+    Function::new(
+        name,
+        vec![SPathType::Nodes],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([nodes]): Option<[NodesType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+            let numbers = members(nodes)
+                .into_iter()
+                .filter_map(Number::from_value)
+                .collect::<Vec<_>>();
+
+            match op(numbers).and_then(Number::into_value) {
+                Some(v) => SPathValue::Value(v),
+                None => SPathValue::Nothing,
+            }
+        }),
+    )
+}
+
+/// Test the predicate against all the members if `all` is true, or any of the members otherwise.
+fn predicate_function<T: VariantValue>(name: &'static str, all: bool) -> Function<T> {
+    Function::new(
+        name,
+        vec![SPathType::Nodes, SPathType::Predicate],
+        SPathType::Logical,
+        Box::new(move |args| {
+            let Some((nodes, predicate)): Option<(NodesType<T>, PredicateType<T>)> =
+                unpack_args(args)
+            else {
+                return SPathValue::Logical(false.into());
+            };
+
+            let mut members = members(nodes).into_iter();
+            let result = if all {
+                members.all(|m| predicate.test(m))
+            } else {
+                members.any(|m| predicate.test(m))
+            };
+
+            SPathValue::Logical(result.into())
+        }),
+    )
+}
+
+/// The members of nodes, with arrays flattened by one level.
+fn members<T: VariantValue>(nodes: NodesType<'_, T>) -> Vec<&T> {
+    let mut members = vec![];
+    for node in nodes {
+        match node.as_array() {
+            Some(array) => members.extend(array.iter()),
+            None => members.push(node),
+        }
+    }
+    members
+}
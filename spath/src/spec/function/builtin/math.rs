@@ -0,0 +1,118 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Math functions, enabled by the `math-functions` feature.
+//!
+//! Integers and floats keep their kinds: operations on integers yield integers whenever the result
+//! is exactly representable, e.g., `div(6, 3)` is `2` while `div(7, 2)` is `3.5`. Functions return
+//! nothing if any argument is not a number, or if the result overflows or is not finite.
+//!
+//! `sum`, `avg`, `min` and `max` are aggregate functions over node lists, which are enabled along
+//! with this feature.
+
+use super::unpack_args;
+use crate::number::Number;
+use crate::spec::function::Function;
+use crate::spec::function::SPathType;
+use crate::spec::function::SPathValue;
+use crate::spec::function::ValueType;
+use crate::VariantValue;
+
+pub fn abs<T: VariantValue>() -> Function<T> {
+    unary_function("abs", Number::abs)
+}
+
+pub fn floor<T: VariantValue>() -> Function<T> {
+    unary_function("floor", |n| Some(n.floor()))
+}
+
+pub fn ceil<T: VariantValue>() -> Function<T> {
+    unary_function("ceil", |n| Some(n.ceil()))
+}
+
+/// `round(x)` rounds half away from zero.
+pub fn round<T: VariantValue>() -> Function<T> {
+    unary_function("round", |n| Some(n.round()))
+}
+
+pub fn add<T: VariantValue>() -> Function<T> {
+    binary_function("add", Number::add)
+}
+
+pub fn sub<T: VariantValue>() -> Function<T> {
+    binary_function("sub", Number::sub)
+}
+
+pub fn mul<T: VariantValue>() -> Function<T> {
+    binary_function("mul", Number::mul)
+}
+
+pub fn div<T: VariantValue>() -> Function<T> {
+    binary_function("div", Number::div)
+}
+
+/// `mod(x, y)` returns the remainder of `x` divided by `y`, with the sign of `x`.
+pub fn rem<T: VariantValue>() -> Function<T> {
+    binary_function("mod", Number::rem)
+}
+
+fn unary_function<T: VariantValue>(
+    name: &'static str,
+    op: fn(Number) -> Option<Number>,
+) -> Function<T> {
+    Function::new(
+        name,
+        vec![SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([value]): Option<[ValueType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+            number_value(as_number(&value).and_then(op))
+        }),
+    )
+}
+
+fn binary_function<T: VariantValue>(
+    name: &'static str,
+    op: fn(Number, Number) -> Option<Number>,
+) -> Function<T> {
+    Function::new(
+        name,
+        vec![SPathType::Value, SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([lhs, rhs]): Option<[ValueType<T>; 2]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+
+            let result = match (as_number(&lhs), as_number(&rhs)) {
+                (Some(l), Some(r)) => op(l, r),
+                _ => None,
+            };
+            number_value(result)
+        }),
+    )
+}
+
+fn as_number<T: VariantValue>(value: &ValueType<'_, T>) -> Option<Number> {
+    value.as_value().and_then(Number::from_value)
+}
+
+fn number_value<'a, T: VariantValue>(number: Option<Number>) -> SPathValue<'a, T> {
+    match number.and_then(Number::into_value) {
+        Some(v) => SPathValue::Value(v),
+        None => SPathValue::Nothing,
+    }
+}
@@ -15,6 +15,8 @@
 use num_traits::ToPrimitive;
 
 use crate::spec::function::Function;
+use crate::spec::function::NodesType;
+use crate::spec::function::PredicateType;
 use crate::spec::function::SPathType;
 use crate::spec::function::SPathValue;
 use crate::spec::function::ValueType;
@@ -23,14 +25,29 @@ use crate::ConcreteVariantObject;
 use crate::Literal;
 use crate::VariantValue;
 
+#[cfg(feature = "aggregate-functions")]
+mod aggregate;
+#[cfg(feature = "aggregate-functions")]
+pub use aggregate::*;
+#[cfg(feature = "math-functions")]
+mod math;
+#[cfg(feature = "math-functions")]
+pub use math::*;
+#[cfg(feature = "string-functions")]
+mod string;
+#[cfg(feature = "string-functions")]
+pub use string::*;
+#[cfg(feature = "type-functions")]
+mod types;
+#[cfg(feature = "type-functions")]
+pub use types::*;
+
 pub fn length<T: VariantValue>() -> Function<T> {
     Function::new(
         "length",
         vec![SPathType::Value],
         SPathType::Value,
-        Box::new(move |mut args| {
-            assert_eq!(args.len(), 1);
-
+        Box::new(move |args| {
             fn value_len<T: VariantValue>(t: &T) -> Option<usize> {
                 if let Some(s) = t.as_str() {
                     Some(s.chars().count())
@@ -41,7 +58,9 @@ pub fn length<T: VariantValue>() -> Function<T> {
                 }
             }
 
-            let value = args.pop().unwrap().into_value().unwrap();
+            let Some([value]): Option<[ValueType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
 
             let len = match value {
                 ValueType::Value(v) => value_len(&v),
@@ -64,10 +83,10 @@ pub fn count<T: VariantValue>() -> Function<T> {
         "count",
         vec![SPathType::Nodes],
         SPathType::Value,
-        Box::new(move |mut args| {
-            assert_eq!(args.len(), 1);
-
-            let nodes = args.pop().unwrap().into_nodes().unwrap();
+        Box::new(move |args| {
+            let Some([nodes]): Option<[NodesType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
 
             let len = nodes
                 .len()
@@ -87,10 +106,10 @@ pub fn value<T: VariantValue>() -> Function<T> {
         "value",
         vec![SPathType::Nodes],
         SPathType::Value,
-        Box::new(move |mut args| {
-            assert_eq!(args.len(), 1);
-
-            let value = args.pop().unwrap().into_nodes().unwrap();
+        Box::new(move |args| {
+            let Some([value]): Option<[NodesType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
             if value.len() > 1 {
                 SPathValue::Nothing
             } else {
@@ -110,11 +129,10 @@ pub fn matches<T: VariantValue>() -> Function<T> {
         "match",
         vec![SPathType::Value, SPathType::Value],
         SPathType::Logical,
-        Box::new(move |mut args| {
-            assert_eq!(args.len(), 2);
-
-            let matcher = args.pop().unwrap().into_value().unwrap();
-            let expr = args.pop().unwrap().into_value().unwrap();
+        Box::new(move |args| {
+            let Some([expr, matcher]): Option<[ValueType<T>; 2]> = unpack_args(args) else {
+                return SPathValue::Logical(false.into());
+            };
 
             let matches = match (
                 matcher.as_value().and_then(|v| v.as_str()),
@@ -137,11 +155,10 @@ pub fn search<T: VariantValue>() -> Function<T> {
         "search",
         vec![SPathType::Value, SPathType::Value],
         SPathType::Logical,
-        Box::new(move |mut args| {
-            assert_eq!(args.len(), 2);
-
-            let matcher = args.pop().unwrap().into_value().unwrap();
-            let expr = args.pop().unwrap().into_value().unwrap();
+        Box::new(move |args| {
+            let Some([expr, matcher]): Option<[ValueType<T>; 2]> = unpack_args(args) else {
+                return SPathValue::Logical(false.into());
+            };
 
             let matches = match (
                 matcher.as_value().and_then(|v| v.as_str()),
@@ -157,3 +174,56 @@ pub fn search<T: VariantValue>() -> Function<T> {
         }),
     )
 }
+
+/// The type of an argument that an evaluated argument unpacks to.
+trait FromArg<'a, T: VariantValue>: Sized {
+    fn from_arg(arg: SPathValue<'a, T>) -> Option<Self>;
+}
+
+impl<'a, T: VariantValue> FromArg<'a, T> for ValueType<'a, T> {
+    fn from_arg(arg: SPathValue<'a, T>) -> Option<Self> {
+        arg.into_value()
+    }
+}
+
+impl<'a, T: VariantValue> FromArg<'a, T> for NodesType<'a, T> {
+    fn from_arg(arg: SPathValue<'a, T>) -> Option<Self> {
+        arg.into_nodes()
+    }
+}
+
+impl<'a, T: VariantValue> FromArg<'a, T> for PredicateType<'a, T> {
+    fn from_arg(arg: SPathValue<'a, T>) -> Option<Self> {
+        arg.into_predicate()
+    }
+}
+
+/// The arguments of a function, unpacked in order of their declared types.
+trait FromArgs<'a, T: VariantValue>: Sized {
+    fn from_args(args: Vec<SPathValue<'a, T>>) -> Option<Self>;
+}
+
+impl<'a, T: VariantValue, A: FromArg<'a, T>, const N: usize> FromArgs<'a, T> for [A; N] {
+    fn from_args(args: Vec<SPathValue<'a, T>>) -> Option<Self> {
+        let args = args
+            .into_iter()
+            .map(A::from_arg)
+            .collect::<Option<Vec<_>>>()?;
+        args.try_into().ok()
+    }
+}
+
+impl<'a, T: VariantValue, A: FromArg<'a, T>, B: FromArg<'a, T>> FromArgs<'a, T> for (A, B) {
+    fn from_args(args: Vec<SPathValue<'a, T>>) -> Option<Self> {
+        let [a, b] = <[SPathValue<'a, T>; 2]>::try_from(args).ok()?;
+        Some((A::from_arg(a)?, B::from_arg(b)?))
+    }
+}
+
+/// Unpack the evaluated arguments of a builtin function to their declared types.
+///
+/// Returns `None` if the number or the type of the arguments does not match, in which case the
+/// function returns nothing, or false if it is a logical function.
+fn unpack_args<'a, T: VariantValue, A: FromArgs<'a, T>>(args: Vec<SPathValue<'a, T>>) -> Option<A> {
+    A::from_args(args)
+}
@@ -0,0 +1,240 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! String functions, enabled by the `string-functions` feature.
+//!
+//! Value type functions return nothing, and logical type functions return false, if any of their
+//! string arguments is not a string. Indices are counted in characters.
+
+use super::unpack_args;
+use crate::spec::function::Function;
+use crate::spec::function::IntoValueType;
+use crate::spec::function::SPathType;
+use crate::spec::function::SPathValue;
+use crate::spec::function::ValueType;
+use crate::Literal;
+use crate::VariantValue;
+
+pub fn starts_with<T: VariantValue>() -> Function<T> {
+    string_predicate("starts_with", |s, prefix| s.starts_with(prefix))
+}
+
+pub fn ends_with<T: VariantValue>() -> Function<T> {
+    string_predicate("ends_with", |s, suffix| s.ends_with(suffix))
+}
+
+pub fn contains<T: VariantValue>() -> Function<T> {
+    string_predicate("contains", |s, needle| s.contains(needle))
+}
+
+pub fn lower<T: VariantValue>() -> Function<T> {
+    string_transform("lower", str::to_lowercase)
+}
+
+pub fn upper<T: VariantValue>() -> Function<T> {
+    string_transform("upper", str::to_uppercase)
+}
+
+pub fn trim<T: VariantValue>() -> Function<T> {
+    string_transform("trim", |s| s.trim().to_string())
+}
+
+/// `substring(s, start, end)` returns the characters of `s` in `[start, end)`.
+///
+/// Negative indices count from the end of the string, and out of range indices are clamped.
+pub fn substring<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "substring",
+        vec![SPathType::Value, SPathType::Value, SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([s, start, end]): Option<[ValueType<T>; 3]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+
+            let result = match (as_str(&s), as_i64(&start), as_i64(&end)) {
+                (Some(s), Some(start), Some(end)) => {
+                    let len = s.chars().count();
+                    let start = normalize_index(start, len).clamp(0, len as i64) as usize;
+                    let end = normalize_index(end, len).clamp(0, len as i64) as usize;
+                    Some(
+                        s.chars()
+                            .skip(start)
+                            .take(end.saturating_sub(start))
+                            .collect::<String>(),
+                    )
+                }
+                _ => None,
+            };
+
+            SPathValue::from(result.into_value_type())
+        }),
+    )
+}
+
+/// `split(s, separator, index)` splits `s` by a non-empty `separator` and returns the piece at
+/// `index`.
+///
+/// A negative index counts from the last piece. Returns nothing if the separator is empty or the
+/// index is out of range.
+pub fn split<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "split",
+        vec![SPathType::Value, SPathType::Value, SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([s, separator, index]): Option<[ValueType<T>; 3]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+
+            let result = match (as_str(&s), as_str(&separator), as_i64(&index)) {
+                (Some(s), Some(separator), Some(index)) if !separator.is_empty() => {
+                    let pieces = s.split(separator).collect::<Vec<_>>();
+                    let index = normalize_index(index, pieces.len());
+                    usize::try_from(index)
+                        .ok()
+                        .and_then(|i| pieces.get(i))
+                        .map(|piece| piece.to_string())
+                }
+                _ => None,
+            };
+
+            SPathValue::from(result.into_value_type())
+        }),
+    )
+}
+
+pub fn concat<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "concat",
+        vec![SPathType::Value, SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([lhs, rhs]): Option<[ValueType<T>; 2]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+
+            let result = match (as_str(&lhs), as_str(&rhs)) {
+                (Some(l), Some(r)) => Some(format!("{l}{r}")),
+                _ => None,
+            };
+
+            SPathValue::from(result.into_value_type())
+        }),
+    )
+}
+
+/// `replace(s, from, to)` replaces all the occurrences of `from` in `s` with `to`.
+pub fn replace<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "replace",
+        vec![SPathType::Value, SPathType::Value, SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([s, from, to]): Option<[ValueType<T>; 3]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+
+            let result = match (as_str(&s), as_str(&from), as_str(&to)) {
+                (Some(s), Some(from), Some(to)) => Some(s.replace(from, to)),
+                _ => None,
+            };
+
+            SPathValue::from(result.into_value_type())
+        }),
+    )
+}
+
+/// `index_of(s, needle)` returns the index of the first occurrence of `needle` in `s`, or -1 if
+/// `needle` is not found.
+pub fn index_of<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "index_of",
+        vec![SPathType::Value, SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([s, needle]): Option<[ValueType<T>; 2]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+
+            let result = match (as_str(&s), as_str(&needle)) {
+                (Some(s), Some(needle)) => Some(match s.find(needle) {
+                    Some(offset) => s[..offset].chars().count() as i64,
+                    None => -1,
+                }),
+                _ => None,
+            };
+
+            SPathValue::from(result.map(Literal::Int).into_value_type())
+        }),
+    )
+}
+
+fn string_predicate<T: VariantValue>(
+    name: &'static str,
+    predicate: fn(&str, &str) -> bool,
+) -> Function<T> {
+    Function::new(
+        name,
+        vec![SPathType::Value, SPathType::Value],
+        SPathType::Logical,
+        Box::new(move |args| {
+            let Some([lhs, rhs]): Option<[ValueType<T>; 2]> = unpack_args(args) else {
+                return SPathValue::Logical(false.into());
+            };
+
+            let result = match (as_str(&lhs), as_str(&rhs)) {
+                (Some(l), Some(r)) => predicate(l, r),
+                _ => false,
+            };
+
+            SPathValue::Logical(result.into())
+        }),
+    )
+}
+
+fn string_transform<T: VariantValue>(
+    name: &'static str,
+    transform: fn(&str) -> String,
+) -> Function<T> {
+    Function::new(
+        name,
+        vec![SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([s]): Option<[ValueType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+            let result = as_str(&s).map(transform);
+
+            SPathValue::from(result.into_value_type())
+        }),
+    )
+}
+
+fn as_str<'a, T: VariantValue>(value: &'a ValueType<'_, T>) -> Option<&'a str> {
+    value.as_value().and_then(|v| v.as_str())
+}
+
+fn as_i64<T: VariantValue>(value: &ValueType<'_, T>) -> Option<i64> {
+    value.as_value().and_then(|v| v.as_i64())
+}
+
+fn normalize_index(index: i64, len: usize) -> i64 {
+    if index >= 0 {
+        index
+    } else {
+        len as i64 + index
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Type introspection functions, enabled by the `type-functions` feature.
+//!
+//! Predicates return false, and `type_of` returns nothing, if the argument is nothing.
+
+use super::unpack_args;
+use crate::spec::function::Function;
+use crate::spec::function::IntoValueType;
+use crate::spec::function::SPathType;
+use crate::spec::function::SPathValue;
+use crate::spec::function::ValueType;
+use crate::VariantValue;
+
+pub fn is_null<T: VariantValue>() -> Function<T> {
+    type_predicate("is_null", |v: &T| v.is_null())
+}
+
+pub fn is_boolean<T: VariantValue>() -> Function<T> {
+    type_predicate("is_boolean", |v: &T| v.is_boolean())
+}
+
+pub fn is_number<T: VariantValue>() -> Function<T> {
+    type_predicate("is_number", |v: &T| v.is_number())
+}
+
+pub fn is_string<T: VariantValue>() -> Function<T> {
+    type_predicate("is_string", |v: &T| v.is_string())
+}
+
+pub fn is_array<T: VariantValue>() -> Function<T> {
+    type_predicate("is_array", |v: &T| v.is_array())
+}
+
+pub fn is_object<T: VariantValue>() -> Function<T> {
+    type_predicate("is_object", |v: &T| v.is_object())
+}
+
+/// `type_of(value)` returns the name of the value's type, one of `'null'`, `'boolean'`,
+/// `'number'`, `'string'`, `'array'` and `'object'`.
+///
+/// Returns nothing for values of other types, e.g., TOML datetimes.
+pub fn type_of<T: VariantValue>() -> Function<T> {
+    Function::new(
+        "type_of",
+        vec![SPathType::Value],
+        SPathType::Value,
+        Box::new(move |args| {
+            let Some([value]): Option<[ValueType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Nothing;
+            };
+            let name = value.as_value().and_then(|v| {
+                if v.is_null() {
+                    Some("null")
+                } else if v.is_boolean() {
+                    Some("boolean")
+                } else if v.is_number() {
+                    Some("number")
+                } else if v.is_string() {
+                    Some("string")
+                } else if v.is_array() {
+                    Some("array")
+                } else if v.is_object() {
+                    Some("object")
+                } else {
+                    None
+                }
+            });
+
+            SPathValue::from(name.into_value_type())
+        }),
+    )
+}
+
+fn type_predicate<T, F>(name: &'static str, predicate: F) -> Function<T>
+where
+    T: VariantValue,
+    F: Fn(&T) -> bool + Send + Sync + 'static,
+{
+    Function::new(
+        name,
+        vec![SPathType::Value],
+        SPathType::Logical,
+        Box::new(move |args| {
+            let Some([value]): Option<[ValueType<T>; 1]> = unpack_args(args) else {
+                return SPathValue::Logical(false.into());
+            };
+            let result = value.as_value().is_some_and(&predicate);
+
+            SPathValue::Logical(result.into())
+        }),
+    )
+}
@@ -0,0 +1,101 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::spec::function::ValueType;
+use crate::Literal;
+use crate::VariantValue;
+
+/// A trait for converting a variant value to a Rust value of function arguments.
+pub trait FromValue<'a, T: VariantValue>: Sized {
+    /// Convert the variant value; [`None`] if the value is not of the expected kind.
+    fn from_value(value: &'a T) -> Option<Self>;
+}
+
+impl<'a, T: VariantValue> FromValue<'a, T> for &'a T {
+    fn from_value(value: &'a T) -> Option<Self> {
+        Some(value)
+    }
+}
+
+impl<'a, T: VariantValue> FromValue<'a, T> for &'a str {
+    fn from_value(value: &'a T) -> Option<Self> {
+        value.as_str()
+    }
+}
+
+impl<T: VariantValue> FromValue<'_, T> for String {
+    fn from_value(value: &T) -> Option<Self> {
+        value.as_str().map(ToString::to_string)
+    }
+}
+
+impl<T: VariantValue> FromValue<'_, T> for bool {
+    fn from_value(value: &T) -> Option<Self> {
+        value.as_bool()
+    }
+}
+
+/// A trait for converting a Rust value of function results to a [`ValueType`].
+pub trait IntoValueType<'a, T: VariantValue> {
+    /// Convert self to a [`ValueType`]; [`ValueType::Nothing`] if it cannot be represented.
+    fn into_value_type(self) -> ValueType<'a, T>;
+}
+
+impl<'a, T: VariantValue> IntoValueType<'a, T> for ValueType<'a, T> {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        self
+    }
+}
+
+impl<'a, T: VariantValue> IntoValueType<'a, T> for &'a T {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        ValueType::Node(self)
+    }
+}
+
+impl<'a, T: VariantValue, V: IntoValueType<'a, T>> IntoValueType<'a, T> for Option<V> {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        match self {
+            Some(v) => v.into_value_type(),
+            None => ValueType::Nothing,
+        }
+    }
+}
+
+impl<'a, T: VariantValue> IntoValueType<'a, T> for Literal {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        match T::from_literal(self) {
+            Some(v) => ValueType::Value(v),
+            None => ValueType::Nothing,
+        }
+    }
+}
+
+impl<'a, T: VariantValue> IntoValueType<'a, T> for String {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        Literal::String(self).into_value_type()
+    }
+}
+
+impl<'a, T: VariantValue> IntoValueType<'a, T> for &str {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        Literal::String(self.to_string()).into_value_type()
+    }
+}
+
+impl<'a, T: VariantValue> IntoValueType<'a, T> for bool {
+    fn into_value_type(self) -> ValueType<'a, T> {
+        Literal::Bool(self).into_value_type()
+    }
+}
@@ -13,14 +13,18 @@
 // limitations under the License.
 
 use std::fmt;
+use std::sync::Arc;
 
 use crate::spec::function::types::FunctionArgType;
 use crate::spec::function::types::SPathType;
 use crate::spec::function::value::LogicalType;
+use crate::spec::function::value::PredicateType;
 use crate::spec::function::value::SPathValue;
-use crate::spec::function::FunctionRegistry;
+use crate::spec::function::Function;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Query;
 use crate::spec::query::Queryable;
+use crate::spec::selector::filter::eval_key;
 use crate::spec::selector::filter::LogicalOrExpr;
 use crate::spec::selector::filter::SingularQuery;
 use crate::spec::selector::filter::TestFilter;
@@ -30,42 +34,34 @@ use crate::VariantValue;
 
 #[doc(hidden)]
 #[derive(Debug, Clone)]
-pub struct FunctionExpr {
+pub struct FunctionExpr<T: VariantValue> {
     pub name: String,
-    pub args: Vec<FunctionExprArg>,
+    pub args: Vec<FunctionExprArg<T>>,
     pub return_type: SPathType,
+    /// The function resolved from the registry at parse time.
+    pub function: Arc<Function<T>>,
 }
 
-impl FunctionExpr {
-    pub fn evaluate<'a, 'b: 'a, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+impl<T: VariantValue> FunctionExpr<T> {
+    pub fn evaluate<'a, 'b: 'a>(
         &'a self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'a, 'b, T>,
     ) -> SPathValue<'a, T> {
         let args: Vec<SPathValue<T>> = self
             .args
             .iter()
-            .map(|a| a.evaluate(current, root, registry))
+            .zip(self.function.argument_types())
+            .map(|(a, ty)| match ty {
+                SPathType::Predicate => SPathValue::Predicate(PredicateType::new(a, ctx)),
+                _ => a.evaluate(current, ctx),
+            })
             .collect();
-        // SAFETY: upon evaluation, the function is guaranteed to be validated
-        let f = registry.get(self.name.as_str()).unwrap();
-        f.evaluate(args)
-    }
-
-    pub fn validate<Registry: FunctionRegistry>(
-        name: String,
-        args: Vec<FunctionExprArg>,
-        registry: &Registry,
-    ) -> Result<(), FunctionValidationError> {
-        let f = registry
-            .get(name.as_str())
-            .ok_or(FunctionValidationError::Undefined { name })?;
-        f.validate(args.as_slice(), registry)
+        self.function.evaluate(args)
     }
 }
 
-impl fmt::Display for FunctionExpr {
+impl<T: VariantValue> fmt::Display for FunctionExpr<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{name}(", name = self.name)?;
         for (i, arg) in self.args.iter().enumerate() {
@@ -79,34 +75,33 @@ impl fmt::Display for FunctionExpr {
     }
 }
 
-impl TestFilter for FunctionExpr {
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool {
-        match self.evaluate(current, root, registry) {
+impl<T: VariantValue> TestFilter<T> for FunctionExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
+        match self.evaluate(current, ctx) {
             SPathValue::Logical(l) => l.into(),
             SPathValue::Nodes(nodes) => !nodes.is_empty(),
             SPathValue::Value(_) => unreachable!("testable function never returns a value"),
             SPathValue::Node(_) => unreachable!("testable function never returns a node"),
             SPathValue::Nothing => unreachable!("testable function never returns nothing"),
+            SPathValue::Predicate(_) => unreachable!("testable function never returns a predicate"),
         }
     }
 }
 
 #[doc(hidden)]
 #[derive(Debug, Clone)]
-pub enum FunctionExprArg {
+pub enum FunctionExprArg<T: VariantValue> {
     Literal(Literal),
     SingularQuery(SingularQuery),
-    FilterQuery(Query),
-    LogicalExpr(LogicalOrExpr),
-    FunctionExpr(FunctionExpr),
+    FilterQuery(Query<T>),
+    LogicalExpr(LogicalOrExpr<T>),
+    FunctionExpr(FunctionExpr<T>),
+    Parameter(String),
+    Binding(String),
+    Key,
 }
 
-impl fmt::Display for FunctionExprArg {
+impl<T: VariantValue> fmt::Display for FunctionExprArg<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             FunctionExprArg::Literal(lit) => write!(f, "{lit}"),
@@ -114,65 +109,71 @@ impl fmt::Display for FunctionExprArg {
             FunctionExprArg::SingularQuery(sq) => write!(f, "{sq}"),
             FunctionExprArg::LogicalExpr(log) => write!(f, "{log}"),
             FunctionExprArg::FunctionExpr(func) => write!(f, "{func}"),
+            FunctionExprArg::Parameter(name) => write!(f, "$${name}"),
+            FunctionExprArg::Binding(name) => write!(f, "${name}"),
+            FunctionExprArg::Key => write!(f, "@key"),
         }
     }
 }
 
-impl FunctionExprArg {
-    fn evaluate<'a, 'b: 'a, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+impl<T: VariantValue> FunctionExprArg<T> {
+    pub(crate) fn evaluate<'a, 'b: 'a>(
         &'a self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'a, 'b, T>,
     ) -> SPathValue<'a, T> {
         match self {
             FunctionExprArg::Literal(lit) => match T::from_literal(lit.clone()) {
                 None => SPathValue::Nothing,
                 Some(v) => SPathValue::Value(v),
             },
-            FunctionExprArg::SingularQuery(q) => match q.eval_query(current, root) {
+            FunctionExprArg::SingularQuery(q) => match q.eval_query(current, ctx) {
                 Some(n) => SPathValue::Node(n),
                 None => SPathValue::Nothing,
             },
             FunctionExprArg::FilterQuery(q) => {
-                let nodes = q.query(current, root, registry);
+                let nodes = q.query(current, ctx);
                 SPathValue::Nodes(NodeList::new(nodes))
             }
-            FunctionExprArg::LogicalExpr(l) => match l.test_filter(current, root, registry) {
+            FunctionExprArg::LogicalExpr(l) => match l.test_filter(current, ctx) {
                 true => SPathValue::Logical(LogicalType::True),
                 false => SPathValue::Logical(LogicalType::False),
             },
-            FunctionExprArg::FunctionExpr(f) => f.evaluate(current, root, registry),
+            FunctionExprArg::FunctionExpr(f) => f.evaluate(current, ctx),
+            FunctionExprArg::Parameter(name) => match ctx.param(name) {
+                Some(v) => SPathValue::Node(v),
+                None => SPathValue::Nothing,
+            },
+            FunctionExprArg::Binding(name) => match ctx.binding(name) {
+                Some(v) => SPathValue::Node(v),
+                None => SPathValue::Nothing,
+            },
+            FunctionExprArg::Key => eval_key(ctx),
         }
     }
 
-    pub fn as_type_kind<Registry: FunctionRegistry>(
-        &self,
-        registry: &Registry,
-    ) -> Result<FunctionArgType, FunctionValidationError> {
+    pub fn as_type_kind(&self) -> FunctionArgType {
         match self {
-            FunctionExprArg::Literal(_) => Ok(FunctionArgType::Literal),
-            FunctionExprArg::SingularQuery(_) => Ok(FunctionArgType::SingularQuery),
+            FunctionExprArg::Literal(_) => FunctionArgType::Literal,
+            FunctionExprArg::SingularQuery(_) => FunctionArgType::SingularQuery,
             FunctionExprArg::FilterQuery(query) => {
                 if query.is_singular() {
-                    Ok(FunctionArgType::SingularQuery)
+                    FunctionArgType::SingularQuery
                 } else {
-                    Ok(FunctionArgType::NodeList)
+                    FunctionArgType::NodeList
                 }
             }
-            FunctionExprArg::LogicalExpr(_) => Ok(FunctionArgType::Logical),
-            FunctionExprArg::FunctionExpr(func) => registry
-                .get(func.name.as_str())
-                .map(|f| f.result_type().as_function_arg_type())
-                .ok_or_else(|| FunctionValidationError::Undefined {
-                    name: func.name.to_string(),
-                }),
+            FunctionExprArg::LogicalExpr(_) => FunctionArgType::Logical,
+            FunctionExprArg::FunctionExpr(func) => func.return_type.as_function_arg_type(),
+            FunctionExprArg::Parameter(_) | FunctionExprArg::Binding(_) | FunctionExprArg::Key => {
+                FunctionArgType::Value
+            }
         }
     }
 }
 
 /// An error occurred while validating a function
-#[derive(Debug, thiserror::Error, PartialEq)]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
 pub enum FunctionValidationError {
     /// Function not defined in inventory
     #[error("function '{name}' is not defined")]
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -21,6 +22,9 @@ use crate::VariantValue;
 
 pub mod builtin;
 
+mod convert;
+pub use convert::*;
+
 mod expr;
 pub use expr::*;
 
@@ -30,7 +34,11 @@ pub use types::*;
 mod value;
 pub use value::*;
 
-pub type Evaluator<T> = Box<dyn Fn(Vec<SPathValue<T>>) -> SPathValue<T>>;
+/// The evaluator of a function.
+///
+/// Evaluators are required to be `Send + Sync` so that functions, possibly capturing state, can be
+/// shared across threads along with parsed queries.
+pub type Evaluator<T> = Box<dyn Fn(Vec<SPathValue<T>>) -> SPathValue<T> + Send + Sync>;
 
 pub struct Function<T: VariantValue> {
     name: &'static str,
@@ -81,29 +89,32 @@ impl<T: VariantValue> Function<T> {
     }
 
     /// Evaluate the function with args.
+    ///
+    /// Each argument is converted to its declared type before being passed to the evaluator.
     pub fn evaluate<'a>(&self, args: Vec<SPathValue<'a, T>>) -> SPathValue<'a, T> {
+        let args = args
+            .into_iter()
+            .zip(self.argument_types.iter())
+            .map(|(arg, ty)| arg.convert_to(*ty))
+            .collect();
         (self.evaluator)(args)
     }
 
     /// Validate the type of function arguments.
-    pub fn validate<Registry: FunctionRegistry<Value = T>>(
-        &self,
-        args: &[FunctionExprArg],
-        registry: &Registry,
-    ) -> Result<(), FunctionValidationError> {
+    pub fn validate(&self, args: &[FunctionExprArg<T>]) -> Result<(), FunctionValidationError> {
         let argument_types = self.argument_types();
 
         if args.len() != argument_types.len() {
             return Err(FunctionValidationError::NumberOfArgsMismatch {
                 name: self.name().to_string(),
-                expected: 1,
+                expected: argument_types.len(),
                 received: args.len(),
             });
         }
 
         for (i, arg) in args.iter().enumerate() {
             let ty = argument_types[i];
-            let kind = arg.as_type_kind(registry)?;
+            let kind = arg.as_type_kind();
             if !kind.converts_to(ty) {
                 return Err(FunctionValidationError::MismatchTypeKind {
                     name: self.name().to_string(),
@@ -121,7 +132,12 @@ impl<T: VariantValue> Function<T> {
 #[doc(hidden)]
 pub trait FunctionRegistry {
     type Value: VariantValue;
-    fn get(&self, name: &str) -> Option<Function<Self::Value>>;
+    fn get(&self, name: &str) -> Option<Arc<Function<Self::Value>>>;
+
+    /// The names of the registered functions, to suggest in place of an undefined one.
+    fn function_names(&self) -> Vec<String> {
+        vec![]
+    }
 }
 
 impl<Registry> FunctionRegistry for Arc<Registry>
@@ -130,9 +146,13 @@ where
 {
     type Value = Registry::Value;
 
-    fn get(&self, name: &str) -> Option<Function<Self::Value>> {
+    fn get(&self, name: &str) -> Option<Arc<Function<Self::Value>>> {
         (**self).get(name)
     }
+
+    fn function_names(&self) -> Vec<String> {
+        (**self).function_names()
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -148,19 +168,158 @@ impl<T: VariantValue> Default for BuiltinFunctionRegistry<T> {
     }
 }
 
-impl<T: VariantValue> FunctionRegistry for BuiltinFunctionRegistry<T> {
-    type Value = T;
+/// A builtin function by its lowercase name, with its constructor.
+type Builtin<T> = (&'static str, fn() -> Function<T>);
 
-    fn get(&self, name: &str) -> Option<Function<Self::Value>> {
-        match name.to_lowercase().as_str() {
-            "count" => Some(count()),
-            "length" => Some(length()),
-            "value" => Some(value()),
+impl<T: VariantValue> BuiltinFunctionRegistry<T> {
+    /// All the builtin functions enabled by the features.
+    fn functions() -> Vec<Builtin<T>> {
+        let functions: &[Builtin<T>] = &[
+            ("count", count),
+            ("length", length),
+            ("value", value),
             #[cfg(feature = "regex")]
-            "match" => Some(matches()),
+            ("match", matches),
             #[cfg(feature = "regex")]
-            "search" => Some(search()),
-            _ => None,
+            ("search", search),
+            #[cfg(feature = "string-functions")]
+            ("starts_with", starts_with),
+            #[cfg(feature = "string-functions")]
+            ("ends_with", ends_with),
+            #[cfg(feature = "string-functions")]
+            ("contains", contains),
+            #[cfg(feature = "string-functions")]
+            ("lower", lower),
+            #[cfg(feature = "string-functions")]
+            ("upper", upper),
+            #[cfg(feature = "string-functions")]
+            ("trim", trim),
+            #[cfg(feature = "string-functions")]
+            ("substring", substring),
+            #[cfg(feature = "string-functions")]
+            ("split", split),
+            #[cfg(feature = "string-functions")]
+            ("concat", concat),
+            #[cfg(feature = "string-functions")]
+            ("replace", replace),
+            #[cfg(feature = "string-functions")]
+            ("index_of", index_of),
+            #[cfg(feature = "aggregate-functions")]
+            ("sum", sum),
+            #[cfg(feature = "aggregate-functions")]
+            ("avg", avg),
+            #[cfg(feature = "aggregate-functions")]
+            ("min", min),
+            #[cfg(feature = "aggregate-functions")]
+            ("max", max),
+            #[cfg(feature = "aggregate-functions")]
+            ("distinct_count", distinct_count),
+            #[cfg(feature = "aggregate-functions")]
+            ("any", any),
+            #[cfg(feature = "aggregate-functions")]
+            ("all", all),
+            #[cfg(feature = "math-functions")]
+            ("abs", abs),
+            #[cfg(feature = "math-functions")]
+            ("floor", floor),
+            #[cfg(feature = "math-functions")]
+            ("ceil", ceil),
+            #[cfg(feature = "math-functions")]
+            ("round", round),
+            #[cfg(feature = "math-functions")]
+            ("add", add),
+            #[cfg(feature = "math-functions")]
+            ("sub", sub),
+            #[cfg(feature = "math-functions")]
+            ("mul", mul),
+            #[cfg(feature = "math-functions")]
+            ("div", div),
+            #[cfg(feature = "math-functions")]
+            ("mod", rem),
+            #[cfg(feature = "type-functions")]
+            ("is_null", is_null),
+            #[cfg(feature = "type-functions")]
+            ("is_boolean", is_boolean),
+            #[cfg(feature = "type-functions")]
+            ("is_number", is_number),
+            #[cfg(feature = "type-functions")]
+            ("is_string", is_string),
+            #[cfg(feature = "type-functions")]
+            ("is_array", is_array),
+            #[cfg(feature = "type-functions")]
+            ("is_object", is_object),
+            #[cfg(feature = "type-functions")]
+            ("type_of", type_of),
+        ];
+        functions.to_vec()
+    }
+}
+
+impl<T: VariantValue> FunctionRegistry for BuiltinFunctionRegistry<T> {
+    type Value = T;
+
+    fn get(&self, name: &str) -> Option<Arc<Function<Self::Value>>> {
+        let name = name.to_lowercase();
+        Self::functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, function)| Arc::new(function()))
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        Self::functions()
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+/// A function registry of user-defined functions, falling back to the builtin functions.
+///
+/// Like [`BuiltinFunctionRegistry`], function names are matched case-insensitively.
+#[derive(Debug, Clone)]
+pub struct CustomFunctionRegistry<T: VariantValue> {
+    builtin: BuiltinFunctionRegistry<T>,
+    functions: BTreeMap<String, Arc<Function<T>>>,
+}
+
+impl<T: VariantValue> Default for CustomFunctionRegistry<T> {
+    fn default() -> Self {
+        Self {
+            builtin: BuiltinFunctionRegistry::default(),
+            functions: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: VariantValue> CustomFunctionRegistry<T> {
+    /// Register a function; a function of the same name is replaced.
+    ///
+    /// The function is shared by all the queries parsed with this registry.
+    pub fn register(&mut self, function: Function<T>) -> &mut Self {
+        let name = function.name().to_lowercase();
+        self.functions.insert(name, Arc::new(function));
+        self
+    }
+}
+
+impl<T: VariantValue> FunctionRegistry for CustomFunctionRegistry<T> {
+    type Value = T;
+
+    fn get(&self, name: &str) -> Option<Arc<Function<Self::Value>>> {
+        match self.functions.get(name.to_lowercase().as_str()) {
+            Some(function) => Some(function.clone()),
+            None => self.builtin.get(name),
+        }
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        let mut names = self.builtin.function_names();
+        for name in self.functions.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
         }
+        names
     }
 }
@@ -23,6 +23,11 @@ pub enum SPathType {
     Value,
     /// A logical value.
     Logical,
+    /// A logical expression evaluated against each node given by the function, e.g., the
+    /// predicate of `any(@.items[*], @.price > 10)`.
+    ///
+    /// This type is only valid for function arguments.
+    Predicate,
 }
 
 impl SPathType {
@@ -31,7 +36,7 @@ impl SPathType {
         match self {
             SPathType::Nodes => FunctionArgType::NodeList,
             SPathType::Value => FunctionArgType::Value,
-            SPathType::Logical => FunctionArgType::Logical,
+            SPathType::Logical | SPathType::Predicate => FunctionArgType::Logical,
         }
     }
 }
@@ -42,6 +47,7 @@ impl fmt::Display for SPathType {
             SPathType::Nodes => write!(f, "nodes type"),
             SPathType::Logical => write!(f, "logical type"),
             SPathType::Value => write!(f, "value type"),
+            SPathType::Predicate => write!(f, "predicate type"),
         }
     }
 }
@@ -99,7 +105,13 @@ impl FunctionArgType {
             ) | (
                 FunctionArgType::NodeList,
                 SPathType::Nodes | SPathType::Logical
-            ) | (FunctionArgType::Logical, SPathType::Logical),
+            ) | (
+                FunctionArgType::Logical,
+                SPathType::Logical | SPathType::Predicate
+            ) | (
+                FunctionArgType::SingularQuery | FunctionArgType::NodeList,
+                SPathType::Predicate
+            ),
         )
     }
 }
@@ -15,6 +15,9 @@
 use std::ops::Deref;
 use std::ops::DerefMut;
 
+use crate::spec::function::FunctionExprArg;
+use crate::spec::function::SPathType;
+use crate::spec::query::EvalContext;
 use crate::NodeList;
 use crate::VariantValue;
 
@@ -26,6 +29,7 @@ pub enum SPathValue<'a, T: VariantValue> {
     Node(&'a T),
     Value(T),
     Nothing,
+    Predicate(PredicateType<'a, T>),
 }
 
 impl<'a, T: VariantValue> SPathValue<'a, T> {
@@ -52,6 +56,14 @@ impl<'a, T: VariantValue> SPathValue<'a, T> {
         }
     }
 
+    /// Convert self to a predicate if possible.
+    pub fn into_predicate(self) -> Option<PredicateType<'a, T>> {
+        match self {
+            SPathValue::Predicate(predicate) => Some(predicate),
+            _ => None,
+        }
+    }
+
     /// Convert self to a singular optional value if possible.
     pub fn into_value(self) -> Option<ValueType<'a, T>> {
         match self {
@@ -61,6 +73,44 @@ impl<'a, T: VariantValue> SPathValue<'a, T> {
             _ => None,
         }
     }
+
+    /// Convert self to a function argument of the declared type.
+    ///
+    /// A singular query argument evaluates to a node or nothing, while it can be declared as
+    /// any type (see [`FunctionArgType`]). Such argument is converted to a node list of at most
+    /// one node, or to the logical value of whether the node list is non-empty. Conversely, a
+    /// singular query with segments like the parent segment `^` evaluates to a node list, which
+    /// is converted to its only node, or nothing if it is empty.
+    ///
+    /// [`FunctionArgType`]: crate::spec::function::FunctionArgType
+    pub(crate) fn convert_to(self, ty: SPathType) -> Self {
+        match (self, ty) {
+            (SPathValue::Node(node), SPathType::Nodes) => {
+                SPathValue::Nodes(NodeList::new(vec![node]))
+            }
+            (SPathValue::Nothing, SPathType::Nodes) => SPathValue::Nodes(NodeList::new(vec![])),
+            (SPathValue::Node(_), SPathType::Logical) => SPathValue::Logical(LogicalType::True),
+            (SPathValue::Nothing, SPathType::Logical) => SPathValue::Logical(LogicalType::False),
+            (SPathValue::Nodes(nodes), SPathType::Logical) => {
+                SPathValue::Logical(LogicalType::from(!nodes.is_empty()))
+            }
+            (SPathValue::Nodes(nodes), SPathType::Value) => match nodes.exactly_one() {
+                Ok(node) => SPathValue::Node(node),
+                Err(_) => SPathValue::Nothing,
+            },
+            (value, _) => value,
+        }
+    }
+}
+
+impl<'a, T: VariantValue> From<ValueType<'a, T>> for SPathValue<'a, T> {
+    fn from(value: ValueType<'a, T>) -> Self {
+        match value {
+            ValueType::Value(value) => SPathValue::Value(value),
+            ValueType::Node(node) => SPathValue::Node(node),
+            ValueType::Nothing => SPathValue::Nothing,
+        }
+    }
 }
 
 /// SPath value representing a node list.
@@ -176,3 +226,33 @@ impl<T: VariantValue> From<T> for ValueType<'_, T> {
         Self::Value(value)
     }
 }
+
+/// SPath predicate, i.e., an unevaluated function argument of [`SPathType::Predicate`].
+///
+/// The function tests the predicate against nodes of its choice, with each node being the current
+/// node `@` of the argument expression.
+#[derive(Debug)]
+pub struct PredicateType<'a, T: VariantValue> {
+    arg: &'a FunctionExprArg<T>,
+    ctx: EvalContext<'a, 'a, T>,
+}
+
+impl<'a, T: VariantValue> PredicateType<'a, T> {
+    pub(crate) fn new(arg: &'a FunctionExprArg<T>, ctx: EvalContext<'a, 'a, T>) -> Self {
+        Self { arg, ctx }
+    }
+
+    /// Test the predicate with `node` as the current node.
+    ///
+    /// A query argument tests whether the query selects any node.
+    pub fn test(&self, node: &T) -> bool {
+        match self.arg.evaluate(node, self.ctx) {
+            SPathValue::Logical(logical) => logical.into(),
+            SPathValue::Nodes(nodes) => !nodes.is_empty(),
+            SPathValue::Node(_) => true,
+            SPathValue::Nothing => false,
+            SPathValue::Value(_) => unreachable!("predicate is never a value"),
+            SPathValue::Predicate(_) => unreachable!("predicate is never nested"),
+        }
+    }
+}
@@ -14,12 +14,19 @@
 
 //! Types representing queries in SPath
 
+use std::collections::BTreeSet;
 use std::fmt;
 
 use super::segment::QuerySegment;
 use crate::node::LocatedNode;
+use crate::node::Node;
 use crate::path::NormalizedPath;
-use crate::spec::function::FunctionRegistry;
+use crate::path::PathElement;
+use crate::spec::function::SPathValue;
+use crate::spec::selector::filter::ArithmeticExpr;
+use crate::Literal;
+use crate::Params;
+use crate::VariantBuilder;
 use crate::VariantValue;
 
 mod sealed {
@@ -30,49 +37,162 @@ mod sealed {
     use crate::spec::selector::index::Index;
     use crate::spec::selector::name::Name;
     use crate::spec::selector::slice::Slice;
+    use crate::spec::selector::sort::Sort;
     use crate::spec::selector::Selector;
 
+    use crate::VariantValue;
+
     pub trait Sealed {}
-    impl Sealed for Query {}
-    impl Sealed for QuerySegment {}
-    impl Sealed for Segment {}
+    impl<T: VariantValue> Sealed for Query<T> {}
+    impl<T: VariantValue> Sealed for QuerySegment<T> {}
+    impl<T: VariantValue> Sealed for Segment<T> {}
     impl Sealed for Slice {}
+    impl Sealed for Sort {}
     impl Sealed for Name {}
-    impl Sealed for Selector {}
+    impl<T: VariantValue> Sealed for Selector<T> {}
     impl Sealed for Index {}
-    impl Sealed for Filter {}
+    impl<T: VariantValue> Sealed for Filter<T> {}
+}
+
+/// The context of evaluating a query, i.e., the root node, the bound parameters, the values of
+/// the `let` bindings, and the parent and member name (or array index) of the current filter
+/// candidate.
+///
+/// The values of the bindings are evaluated along with the query, and thus may live shorter than
+/// the queried value `'b`, as `'c`.
+#[derive(Debug)]
+pub struct EvalContext<'c, 'b, T: VariantValue> {
+    root: &'b T,
+    params: Option<&'b Params<T>>,
+    bindings: Option<&'c BindingScope<'c, T>>,
+    parent: Option<&'b T>,
+    key: Option<PathElement<'b>>,
+}
+
+impl<T: VariantValue> Clone for EvalContext<'_, '_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: VariantValue> Copy for EvalContext<'_, '_, T> {}
+
+impl<'c, 'b, T: VariantValue> EvalContext<'c, 'b, T> {
+    /// Create a new evaluation context with the `root` node and no parameters bound.
+    pub fn new(root: &'b T) -> Self {
+        Self {
+            root,
+            params: None,
+            bindings: None,
+            parent: None,
+            key: None,
+        }
+    }
+
+    /// Bind the given parameters to the context.
+    pub fn with_params(mut self, params: &'b Params<T>) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// The root node, i.e., `$`.
+    pub fn root(&self) -> &'b T {
+        self.root
+    }
+
+    /// The value bound to the parameter `name`, or `None` if it's unbound or nothing.
+    pub fn param(&self, name: &str) -> Option<&'b T> {
+        self.params.and_then(|params| params.get(name))
+    }
+
+    /// Bring the value of a `let` binding into scope.
+    fn with_binding<'d>(self, scope: &'d BindingScope<'d, T>) -> EvalContext<'d, 'b, T>
+    where
+        'c: 'd,
+    {
+        EvalContext {
+            root: self.root,
+            params: self.params,
+            bindings: Some(scope),
+            parent: self.parent,
+            key: self.key,
+        }
+    }
+
+    /// The value of the `let` binding `$name`, or `None` if it's not in scope or nothing.
+    pub fn binding(&self, name: &str) -> Option<&'c T> {
+        let mut scope = self.bindings;
+        while let Some(s) = scope {
+            if s.name == name {
+                return s.value;
+            }
+            scope = s.parent;
+        }
+        None
+    }
+
+    /// Set the parent of the current filter candidates, i.e., the node the filter applies to.
+    pub(crate) fn with_parent(mut self, parent: &'b T) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// The parent of the current filter candidate, i.e., `@^`, or `None` if not within a filter.
+    pub fn parent(&self) -> Option<&'b T> {
+        self.parent
+    }
+
+    /// Set the member name (or array index) of the current filter candidate.
+    pub(crate) fn with_key(mut self, key: impl Into<PathElement<'b>>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// The member name (or array index) of the current filter candidate, i.e., `@key`, or
+    /// `None` if not within a filter.
+    pub fn key(&self) -> Option<PathElement<'b>> {
+        self.key
+    }
 }
 
 /// A trait that can query a variant value.
-pub trait Queryable: sealed::Sealed {
-    /// Run the query over a `current` node with a `root` node.
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> Vec<&'b T>;
+pub trait Queryable<T: VariantValue>: sealed::Sealed {
+    /// Run the query over a `current` node within the evaluation context.
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T>;
 
-    /// Run the query over a `current` node with a `root` node and a `parent` path.
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    /// Run the query over a `current` node within the evaluation context and a `parent` path.
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'_, 'b, T>,
         parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>>;
 }
 
 /// Represents an SPath expression
-#[derive(Debug, Clone, Default)]
-pub struct Query {
-    /// The kind of query, root (`$`), or current (`@`)
+#[derive(Debug, Clone)]
+pub struct Query<T: VariantValue> {
+    /// The kind of query, root (`$`), current (`@`), or parent (`@^`)
     pub kind: QueryKind,
     /// The segments constituting the query
-    pub segments: Vec<QuerySegment>,
+    pub segments: Vec<QuerySegment<T>>,
+}
+
+impl<T: VariantValue> Default for Query<T> {
+    fn default() -> Self {
+        Self {
+            kind: QueryKind::default(),
+            segments: vec![],
+        }
+    }
 }
 
-impl Query {
+impl<T: VariantValue> Query<T> {
+    /// Whether this query contains parent segments, which are resolved with locations.
+    pub fn has_parent_segments(&self) -> bool {
+        self.segments.iter().any(|s| s.segment.is_parent())
+    }
+
     /// Whether this query extracts at most a singular node.
     pub fn is_singular(&self) -> bool {
         for s in &self.segments {
@@ -87,11 +207,12 @@ impl Query {
     }
 }
 
-impl fmt::Display for Query {
+impl<T: VariantValue> fmt::Display for Query<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
             QueryKind::Root => write!(f, "$")?,
             QueryKind::Current => write!(f, "@")?,
+            QueryKind::Parent => write!(f, "@^")?,
         }
         for s in &self.segments {
             write!(f, "{s}")?;
@@ -108,49 +229,432 @@ pub enum QueryKind {
     Root,
     /// A query against the current node within a variant object, i.e., with `@`
     Current,
+    /// A query against the parent of the current node within a filter, i.e., with `@^`
+    ///
+    /// This is an extension beyond RFC 9535.
+    Parent,
 }
 
-impl Queryable for Query {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Query<T> {
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        if self.has_parent_segments() {
+            // parent segments are only allowed in root queries, whose locations start at root
+            let nodes = self.query_located(current, ctx, NormalizedPath::default());
+            return nodes.into_iter().map(|n| n.node()).collect();
+        }
+
         let mut result = match self.kind {
-            QueryKind::Root => vec![root],
+            QueryKind::Root => vec![ctx.root()],
             QueryKind::Current => vec![current],
+            QueryKind::Parent => ctx.parent().into_iter().collect(),
         };
         for segment in &self.segments {
             let mut r = Vec::new();
             for node in result {
-                r.append(&mut segment.query(node, root, registry));
+                r.append(&mut segment.query(node, ctx));
             }
             result = r;
         }
         result
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'_, 'b, T>,
         parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
         let mut result = match self.kind {
             QueryKind::Current => vec![LocatedNode::new(parent, current)],
-            QueryKind::Root => vec![LocatedNode::new(Default::default(), root)],
+            QueryKind::Root => vec![LocatedNode::new(Default::default(), ctx.root())],
+            QueryKind::Parent => {
+                // the parent path is the location of the current node within a filter
+                let mut parent = parent;
+                match (parent.pop(), ctx.parent()) {
+                    (Some(_), Some(node)) => vec![LocatedNode::new(parent, node)],
+                    _ => vec![],
+                }
+            }
         };
         for s in &self.segments {
             let mut r = vec![];
             for n in result {
                 let loc = n.location();
                 let node = n.node();
-                r.append(&mut s.query_located(node, root, registry, loc.clone()));
+                r.append(&mut s.query_located(node, ctx, loc.clone()));
             }
             result = r;
         }
         result
     }
 }
+
+/// The main query of an SPath, i.e., a root query optionally extended beyond RFC 9535.
+///
+/// The main query is a union of branches, e.g., `$.errors[*] | $.warnings[*]`, whose results
+/// are concatenated in order, optionally preceded by `let` bindings, e.g.,
+/// `let $max = max($.items[*].price); $.items[?@.price == $max]`. A query conforming to
+/// RFC 9535 has exactly one branch and no bindings.
+#[derive(Debug, Clone)]
+pub struct MainQuery<T: VariantValue> {
+    /// The `let` bindings, in order
+    pub bindings: Vec<Binding<T>>,
+    /// The branches of the union, in order
+    pub branches: Vec<QueryBranch<T>>,
+    /// The names of the parameters referenced by the query
+    pub params: BTreeSet<String>,
+}
+
+impl<T: VariantValue> MainQuery<T> {
+    /// Whether this query only yields nodes borrowed from the queried value.
+    pub fn is_borrowed(&self) -> bool {
+        self.branches.iter().all(QueryBranch::is_borrowed)
+    }
+
+    /// Run the query within the evaluation context, yielding the borrowed nodes only.
+    pub fn query<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        self.bind(ctx, |ctx| {
+            self.branches
+                .iter()
+                .flat_map(|branch| branch.query(ctx))
+                .collect()
+        })
+    }
+
+    /// Run the query within the evaluation context, yielding the borrowed located nodes only.
+    pub fn query_located<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<LocatedNode<'b, T>> {
+        self.bind(ctx, |ctx| {
+            self.branches
+                .iter()
+                .flat_map(|branch| branch.query_located(ctx))
+                .collect()
+        })
+    }
+
+    /// Run the query within the evaluation context, yielding both borrowed and synthesized nodes.
+    ///
+    /// Projections construct new values, and thus are only evaluated by
+    /// [`MainQuery::query_projected`].
+    pub fn query_nodes<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<Node<'b, T>> {
+        self.bind(ctx, |ctx| {
+            self.branches
+                .iter()
+                .flat_map(|branch| branch.query_nodes(ctx))
+                .collect()
+        })
+    }
+
+    /// Run the query within the evaluation context, yielding owned values, including those
+    /// constructed by the projection.
+    pub fn query_projected(&self, ctx: EvalContext<'_, '_, T>) -> Vec<T>
+    where
+        T: VariantBuilder,
+    {
+        self.bind(ctx, |ctx| {
+            self.branches
+                .iter()
+                .flat_map(|branch| branch.query_projected(ctx))
+                .collect()
+        })
+    }
+
+    /// Evaluate the bindings once, in order, and run `f` with their values in scope.
+    fn bind<'b, R>(
+        &self,
+        ctx: EvalContext<'_, 'b, T>,
+        f: impl FnOnce(EvalContext<'_, 'b, T>) -> R,
+    ) -> R {
+        bind_in_scope(&self.bindings, ctx, f)
+    }
+}
+
+impl<T: VariantValue> fmt::Display for MainQuery<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for binding in &self.bindings {
+            write!(f, "{binding} ")?;
+        }
+        for (i, branch) in self.branches.iter().enumerate() {
+            let sep = if i == 0 { "" } else { " | " };
+            write!(f, "{sep}{branch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A `let` binding of the [`MainQuery`], e.g., `let $max = max($.items[*].price);`.
+///
+/// The value is evaluated once per evaluation of the query, with the root node as the current
+/// node `@`. The binding is in scope as `$max` for the bindings following it and the rest of the
+/// query; a binding can't be redefined. This is an extension beyond RFC 9535.
+#[derive(Debug, Clone)]
+pub struct Binding<T: VariantValue> {
+    /// The name of the binding, without the leading `$`
+    pub name: String,
+    /// The bound expression
+    pub value: ArithmeticExpr<T>,
+}
+
+impl<T: VariantValue> fmt::Display for Binding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "let ${name} = {value};",
+            name = self.name,
+            value = self.value
+        )
+    }
+}
+
+/// The value of a `let` binding in scope, linked to the bindings preceding it.
+#[derive(Debug)]
+struct BindingScope<'c, T: VariantValue> {
+    name: &'c str,
+    value: Option<&'c T>,
+    parent: Option<&'c BindingScope<'c, T>>,
+}
+
+// each value is kept on the stack while the following bindings and `f` are evaluated, so that
+// values borrowed from the queried value need not be cloned
+fn bind_in_scope<'b, T: VariantValue, R>(
+    bindings: &[Binding<T>],
+    ctx: EvalContext<'_, 'b, T>,
+    f: impl FnOnce(EvalContext<'_, 'b, T>) -> R,
+) -> R {
+    let Some((binding, rest)) = bindings.split_first() else {
+        return f(ctx);
+    };
+    let value = binding.value.as_value(ctx.root(), ctx);
+    let scope = BindingScope {
+        name: &binding.name,
+        value: match &value {
+            SPathValue::Node(v) => Some(*v),
+            SPathValue::Value(v) => Some(v),
+            _ => None,
+        },
+        parent: ctx.bindings,
+    };
+    bind_in_scope(rest, ctx.with_binding(&scope), f)
+}
+
+/// A branch of the [`MainQuery`] union, i.e., a root query with an optional pipeline and suffix.
+#[derive(Debug, Clone)]
+pub struct QueryBranch<T: VariantValue> {
+    /// The root query
+    pub query: Query<T>,
+    /// The stages of the pipeline, e.g., `=> @.author`, each evaluated with each result of the
+    /// previous stage as the current node `@`
+    pub pipeline: Vec<Query<T>>,
+    /// Whether to select the member names (or array indices) of the resulting nodes, i.e.,
+    /// with a trailing `~`
+    pub keys: bool,
+    /// The projection constructing an object from each resulting node, i.e., with a trailing
+    /// `.{...}`
+    pub projection: Option<Projection<T>>,
+}
+
+impl<T: VariantValue> QueryBranch<T> {
+    /// Whether this branch only yields nodes borrowed from the queried value.
+    pub fn is_borrowed(&self) -> bool {
+        !self.keys && self.projection.is_none()
+    }
+
+    /// Run the branch within the evaluation context, yielding the borrowed nodes only.
+    pub fn query<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        if !self.is_borrowed() {
+            return vec![];
+        }
+        self.select(ctx)
+    }
+
+    /// Run the branch within the evaluation context, yielding the borrowed located nodes only.
+    pub fn query_located<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<LocatedNode<'b, T>> {
+        if !self.is_borrowed() {
+            return vec![];
+        }
+        self.select_located(ctx)
+    }
+
+    /// Run the branch within the evaluation context, yielding both borrowed and synthesized
+    /// nodes, except for the values constructed by the projection.
+    pub fn query_nodes<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<Node<'b, T>> {
+        if self.projection.is_some() {
+            return vec![];
+        }
+        if !self.keys {
+            let nodes = self.select(ctx);
+            return nodes.into_iter().map(Node::Borrowed).collect();
+        }
+
+        let nodes = self.select_located(ctx);
+        nodes
+            .into_iter()
+            .filter_map(|node| {
+                let key = node.location().last()?.to_literal();
+                T::from_literal(key).map(Node::Owned)
+            })
+            .collect()
+    }
+
+    /// Run the branch within the evaluation context, yielding owned values, including those
+    /// constructed by the projection.
+    pub fn query_projected(&self, ctx: EvalContext<'_, '_, T>) -> Vec<T>
+    where
+        T: VariantBuilder,
+    {
+        match &self.projection {
+            Some(projection) => {
+                let nodes = self.select(ctx);
+                nodes
+                    .into_iter()
+                    .map(|node| projection.project(node, ctx))
+                    .collect()
+            }
+            None => {
+                let nodes = self.query_nodes(ctx);
+                nodes.into_iter().map(Node::into_owned).collect()
+            }
+        }
+    }
+
+    /// Select the nodes of the root query and then the pipeline.
+    fn select<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        let mut result = self.query.query(ctx.root(), ctx);
+        for stage in &self.pipeline {
+            result = result
+                .into_iter()
+                .flat_map(|node| stage.query(node, ctx))
+                .collect();
+        }
+        result
+    }
+
+    /// Select the located nodes of the root query and then the pipeline.
+    ///
+    /// Each stage starts from the location of its current node, so locations stay absolute.
+    fn select_located<'b>(&self, ctx: EvalContext<'_, 'b, T>) -> Vec<LocatedNode<'b, T>> {
+        let root = ctx.root();
+        let mut result = self
+            .query
+            .query_located(root, ctx, NormalizedPath::default());
+        for stage in &self.pipeline {
+            result = result
+                .into_iter()
+                .flat_map(|node| stage.query_located(node.node(), ctx, node.into_location()))
+                .collect();
+        }
+        result
+    }
+}
+
+impl<T: VariantValue> fmt::Display for QueryBranch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.query)?;
+        for stage in &self.pipeline {
+            write!(f, " => {stage}")?;
+        }
+        if self.keys {
+            write!(f, "~")?;
+        }
+        if let Some(projection) = &self.projection {
+            write!(f, "{projection}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A projection constructing an object from each resulting node, e.g.,
+/// `.{id: @.id, mail: @.contact.email}`.
+///
+/// This is an extension beyond RFC 9535.
+#[derive(Debug, Clone)]
+pub struct Projection<T: VariantValue> {
+    /// The members of the constructed object, in order
+    pub members: Vec<ProjectionMember<T>>,
+}
+
+impl<T: VariantValue> Projection<T> {
+    /// Construct the object from the `current` node, i.e., `@`.
+    ///
+    /// Members whose value is nothing are omitted.
+    pub fn project<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> T
+    where
+        T: VariantBuilder,
+    {
+        let members = self
+            .members
+            .iter()
+            .filter_map(|member| {
+                let value = member.value.evaluate(current, ctx)?;
+                Some((member.name.clone(), value))
+            })
+            .collect();
+        T::build_object(members)
+    }
+}
+
+impl<T: VariantValue> fmt::Display for Projection<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ".{{")?;
+        for (i, member) in self.members.iter().enumerate() {
+            let comma = if i == self.members.len() - 1 { "" } else { "," };
+            write!(f, "{member}{comma}")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// A member of a [`Projection`], e.g., `mail: @.contact.email`.
+#[derive(Debug, Clone)]
+pub struct ProjectionMember<T: VariantValue> {
+    /// The member name
+    pub name: String,
+    /// The member value
+    pub value: ProjectionValue<T>,
+}
+
+impl<T: VariantValue> fmt::Display for ProjectionMember<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // escape the name like a normalized path does, e.g., `'it\'s'`
+        let name = PathElement::Name(&self.name);
+        write!(f, "'{name}':{value}", value = self.value)
+    }
+}
+
+/// The value of a [`ProjectionMember`].
+#[derive(Debug, Clone)]
+pub enum ProjectionValue<T: VariantValue> {
+    /// A literal value
+    Literal(Literal),
+    /// A query, which evaluates to the node it selects, or nothing, if it's singular, or to an
+    /// array of the nodes it selects otherwise
+    Query(Query<T>),
+}
+
+impl<T: VariantValue> ProjectionValue<T> {
+    fn evaluate<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Option<T>
+    where
+        T: VariantBuilder,
+    {
+        match self {
+            ProjectionValue::Literal(lit) => T::from_literal(lit.clone()),
+            ProjectionValue::Query(query) => {
+                let nodes = query.query(current, ctx);
+                if query.is_singular() {
+                    nodes.first().map(|node| (*node).clone())
+                } else {
+                    let elements = nodes.into_iter().cloned().collect();
+                    Some(T::build_array(elements))
+                }
+            }
+        }
+    }
+}
+
+impl<T: VariantValue> fmt::Display for ProjectionValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionValue::Literal(lit) => write!(f, "{lit}"),
+            ProjectionValue::Query(query) => write!(f, "{query}"),
+        }
+    }
+}
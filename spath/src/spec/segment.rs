@@ -14,7 +14,7 @@
 
 use std::fmt;
 
-use crate::spec::function::FunctionRegistry;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Queryable;
 use crate::spec::select_wildcard;
 use crate::spec::selector::Selector;
@@ -26,14 +26,14 @@ use crate::VariantValue;
 
 /// A segment of a SPath query
 #[derive(Debug, Clone)]
-pub struct QuerySegment {
+pub struct QuerySegment<T: VariantValue> {
     /// The kind of segment
     pub kind: QuerySegmentKind,
     /// The segment
-    pub segment: Segment,
+    pub segment: Segment<T>,
 }
 
-impl QuerySegment {
+impl<T: VariantValue> QuerySegment<T> {
     /// Is this a normal child segment
     pub fn is_child(&self) -> bool {
         matches!(self.kind, QuerySegmentKind::Child)
@@ -45,82 +45,169 @@ impl QuerySegment {
     }
 }
 
-impl fmt::Display for QuerySegment {
+impl<T: VariantValue> fmt::Display for QuerySegment<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if matches!(self.kind, QuerySegmentKind::Descendant) {
-            write!(f, "..")?;
+        match self.kind {
+            QuerySegmentKind::Child => return write!(f, "{}", self.segment),
+            QuerySegmentKind::Descendant => write!(f, "..")?,
+            QuerySegmentKind::BoundedDescendant { min, max } => {
+                write!(f, "..{{{min},")?;
+                if let Some(max) = max {
+                    write!(f, "{max}")?;
+                }
+                write!(f, "}}")?;
+            }
+        }
+        // the shorthands are written without their leading dot, e.g., `..name` or `..{1,2}name`
+        match &self.segment {
+            Segment::DotName(name) => write!(f, "{name}"),
+            Segment::Wildcard => write!(f, "*"),
+            segment => write!(f, "{segment}"),
         }
-        write!(f, "{}", self.segment)
     }
 }
 
-impl Queryable for QuerySegment {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> Vec<&'b T> {
-        let mut query = self.segment.query(current, root, registry);
+impl<T: VariantValue> Queryable<T> for QuerySegment<T> {
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        if let QuerySegmentKind::BoundedDescendant { min, max } = self.kind {
+            return descend_within(&self.segment, current, ctx, 1, min, max);
+        }
+
+        let mut query = self.segment.query(current, ctx);
         if matches!(self.kind, QuerySegmentKind::Descendant) {
-            query.append(&mut descend(self, current, root, registry));
+            query.append(&mut descend(self, current, ctx));
         }
         query
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'_, 'b, T>,
         parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
-        if matches!(self.kind, QuerySegmentKind::Descendant) {
-            let mut result = self
-                .segment
-                .query_located(current, root, registry, parent.clone());
-            result.append(&mut descend_paths(self, current, root, registry, parent));
-            result
-        } else {
-            self.segment.query_located(current, root, registry, parent)
+        match self.kind {
+            QuerySegmentKind::Child => self.segment.query_located(current, ctx, parent),
+            QuerySegmentKind::Descendant => {
+                let mut result = self.segment.query_located(current, ctx, parent.clone());
+                result.append(&mut descend_paths(self, current, ctx, parent));
+                result
+            }
+            QuerySegmentKind::BoundedDescendant { min, max } => {
+                descend_paths_within(&self.segment, current, ctx, parent, 1, min, max)
+            }
         }
     }
 }
 
-fn descend<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-    segment: &QuerySegment,
+/// Apply the `segment` to `current` and its descendants, selecting the nodes whose depth below
+/// the input node is within `min..=max`; `current` itself is at `depth - 1`.
+fn descend_within<'b, T: VariantValue>(
+    segment: &Segment<T>,
     current: &'b T,
-    root: &'b T,
-    registry: &Registry,
+    ctx: EvalContext<'_, 'b, T>,
+    depth: usize,
+    min: usize,
+    max: Option<usize>,
 ) -> Vec<&'b T> {
     let mut query = Vec::new();
+    if depth >= min {
+        query.append(&mut segment.query(current, ctx));
+    }
+    if max.is_some_and(|max| depth >= max) {
+        return query;
+    }
     if let Some(list) = current.as_array() {
         for v in list.iter() {
-            query.append(&mut segment.query(v, root, registry));
+            query.append(&mut descend_within(segment, v, ctx, depth + 1, min, max));
         }
     } else if let Some(obj) = current.as_object() {
         for v in obj.values() {
-            query.append(&mut segment.query(v, root, registry));
+            query.append(&mut descend_within(segment, v, ctx, depth + 1, min, max));
         }
     }
     query
 }
 
-fn descend_paths<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-    segment: &QuerySegment,
+/// The located version of [`descend_within`].
+fn descend_paths_within<'b, T: VariantValue>(
+    segment: &Segment<T>,
     current: &'b T,
-    root: &'b T,
-    registry: &Registry,
+    ctx: EvalContext<'_, 'b, T>,
     parent: NormalizedPath<'b>,
+    depth: usize,
+    min: usize,
+    max: Option<usize>,
 ) -> Vec<LocatedNode<'b, T>> {
     let mut result = Vec::new();
+    if depth >= min {
+        result.append(&mut segment.query_located(current, ctx, parent.clone()));
+    }
+    if max.is_some_and(|max| depth >= max) {
+        return result;
+    }
     if let Some(list) = current.as_array() {
         for (i, v) in list.iter().enumerate() {
-            result.append(&mut segment.query_located(v, root, registry, parent.clone_and_push(i)));
+            let path = parent.clone_and_push(i);
+            result.append(&mut descend_paths_within(
+                segment,
+                v,
+                ctx,
+                path,
+                depth + 1,
+                min,
+                max,
+            ));
         }
     } else if let Some(obj) = current.as_object() {
         for (k, v) in obj.iter() {
-            result.append(&mut segment.query_located(v, root, registry, parent.clone_and_push(k)));
+            let path = parent.clone_and_push(k);
+            result.append(&mut descend_paths_within(
+                segment,
+                v,
+                ctx,
+                path,
+                depth + 1,
+                min,
+                max,
+            ));
+        }
+    }
+    result
+}
+
+fn descend<'b, T: VariantValue>(
+    segment: &QuerySegment<T>,
+    current: &'b T,
+    ctx: EvalContext<'_, 'b, T>,
+) -> Vec<&'b T> {
+    let mut query = Vec::new();
+    if let Some(list) = current.as_array() {
+        for v in list.iter() {
+            query.append(&mut segment.query(v, ctx));
+        }
+    } else if let Some(obj) = current.as_object() {
+        for v in obj.values() {
+            query.append(&mut segment.query(v, ctx));
+        }
+    }
+    query
+}
+
+fn descend_paths<'b, T: VariantValue>(
+    segment: &QuerySegment<T>,
+    current: &'b T,
+    ctx: EvalContext<'_, 'b, T>,
+    parent: NormalizedPath<'b>,
+) -> Vec<LocatedNode<'b, T>> {
+    let mut result = Vec::new();
+    if let Some(list) = current.as_array() {
+        for (i, v) in list.iter().enumerate() {
+            result.append(&mut segment.query_located(v, ctx, parent.clone_and_push(i)));
+        }
+    } else if let Some(obj) = current.as_object() {
+        for (k, v) in obj.iter() {
+            result.append(&mut segment.query_located(v, ctx, parent.clone_and_push(k)));
         }
     }
     result
@@ -137,20 +224,36 @@ pub enum QuerySegmentKind {
     ///
     /// Addresses all descendant children of the preceding segment, recursively
     Descendant,
+    /// A descendant child within a depth range, e.g., `..{1,2}`
+    ///
+    /// Addresses the descendant children of the preceding segment whose depth below the
+    /// preceding node is within `min..=max`, where the direct children are at depth 1. This is
+    /// an extension beyond RFC 9535.
+    BoundedDescendant {
+        /// The minimum depth, at least 1
+        min: usize,
+        /// The maximum depth, or unbounded if `None`
+        max: Option<usize>,
+    },
 }
 
 /// Represents the different forms of SPath segment.
 #[derive(Debug, Clone)]
-pub enum Segment {
+pub enum Segment<T: VariantValue> {
     /// Long hand segments contain multiple selectors inside square brackets.
-    LongHand(Vec<Selector>),
+    LongHand(Vec<Selector<T>>),
     /// Dot-name selectors are a short form for representing keys in an object.
     DotName(String),
     /// The wildcard shorthand `.*`.
     Wildcard,
+    /// The parent segment `^`, addressing the parent of the preceding node.
+    ///
+    /// This is an extension beyond RFC 9535. It's resolved with the location of the preceding
+    /// node, and thus only allowed in root queries.
+    Parent,
 }
 
-impl Segment {
+impl<T: VariantValue> Segment<T> {
     /// Whether this segment extracts at most a singular node.
     pub fn is_singular(&self) -> bool {
         match self {
@@ -169,17 +272,23 @@ impl Segment {
             }
             Segment::DotName(_) => true,
             Segment::Wildcard => false,
+            Segment::Parent => true,
         }
     }
 
     /// Optionally produce self as a slice of selectors, from a long hand segment.
-    pub fn as_long_hand(&self) -> Option<&[Selector]> {
+    pub fn as_long_hand(&self) -> Option<&[Selector<T>]> {
         match self {
             Segment::LongHand(v) => Some(v.as_slice()),
             _ => None,
         }
     }
 
+    /// Whether this is a parent segment.
+    pub fn is_parent(&self) -> bool {
+        matches!(self, Segment::Parent)
+    }
+
     /// Optionally produce self as a single name segment.
     pub fn as_dot_name(&self) -> Option<&str> {
         match self {
@@ -189,7 +298,7 @@ impl Segment {
     }
 }
 
-impl fmt::Display for Segment {
+impl<T: VariantValue> fmt::Display for Segment<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Segment::LongHand(selectors) => {
@@ -205,23 +314,19 @@ impl fmt::Display for Segment {
             }
             Segment::DotName(name) => write!(f, ".{name}")?,
             Segment::Wildcard => write!(f, ".*")?,
+            Segment::Parent => write!(f, "^")?,
         }
         Ok(())
     }
 }
 
-impl Queryable for Segment {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Segment<T> {
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
         let mut result = Vec::new();
         match self {
             Segment::LongHand(selectors) => {
                 for selector in selectors {
-                    result.append(&mut selector.query(current, root, registry));
+                    result.append(&mut selector.query(current, ctx));
                 }
             }
             Segment::DotName(key) => {
@@ -232,22 +337,23 @@ impl Queryable for Segment {
                 }
             }
             Segment::Wildcard => select_wildcard(&mut result, current),
+            // the parent is resolved with locations; see `Query::query`
+            Segment::Parent => {}
         }
         result
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'_, 'b, T>,
         mut parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
         let mut result = vec![];
         match self {
             Segment::LongHand(selectors) => {
                 for s in selectors {
-                    result.append(&mut s.query_located(current, root, registry, parent.clone()));
+                    result.append(&mut s.query_located(current, ctx, parent.clone()));
                 }
             }
             Segment::DotName(name) => {
@@ -267,6 +373,13 @@ impl Queryable for Segment {
                     }
                 }
             }
+            Segment::Parent => {
+                if parent.pop().is_some() {
+                    if let Some(node) = parent.resolve(ctx.root()) {
+                        result.push(LocatedNode::new(parent, node));
+                    }
+                }
+            }
         }
         result
     }
@@ -20,10 +20,11 @@ use super::index::Index;
 use super::name::Name;
 use super::Selector;
 use crate::node::LocatedNode;
+use crate::number::Number;
 use crate::path::NormalizedPath;
 use crate::spec::function::FunctionExpr;
-use crate::spec::function::FunctionRegistry;
 use crate::spec::function::SPathValue;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Query;
 use crate::spec::query::QueryKind;
 use crate::spec::query::Queryable;
@@ -40,75 +41,73 @@ mod sealed {
     use super::ExistExpr;
     use super::LogicalAndExpr;
     use super::LogicalOrExpr;
+    #[cfg(feature = "regex")]
+    use super::MatchExpr;
     use crate::spec::function::FunctionExpr;
+    use crate::VariantValue;
 
     pub trait Sealed {}
-    impl Sealed for LogicalOrExpr {}
-    impl Sealed for LogicalAndExpr {}
-    impl Sealed for BasicExpr {}
-    impl Sealed for ExistExpr {}
-    impl Sealed for ComparisonExpr {}
-    impl Sealed for FunctionExpr {}
+    impl<T: VariantValue> Sealed for LogicalOrExpr<T> {}
+    impl<T: VariantValue> Sealed for LogicalAndExpr<T> {}
+    impl<T: VariantValue> Sealed for BasicExpr<T> {}
+    impl<T: VariantValue> Sealed for ExistExpr<T> {}
+    impl<T: VariantValue> Sealed for ComparisonExpr<T> {}
+    impl<T: VariantValue> Sealed for FunctionExpr<T> {}
+    #[cfg(feature = "regex")]
+    impl<T: VariantValue> Sealed for MatchExpr<T> {}
 }
 
 /// Trait for testing a filter type.
-pub trait TestFilter: sealed::Sealed {
-    /// Test self using the current and root nodes.
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool;
+pub trait TestFilter<T: VariantValue>: sealed::Sealed {
+    /// Test self using the current node within the evaluation context.
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool;
 }
 
 /// The main filter type for SPath.
 #[derive(Debug, Clone)]
-pub struct Filter(pub LogicalOrExpr);
+pub struct Filter<T: VariantValue>(pub LogicalOrExpr<T>);
 
-impl fmt::Display for Filter {
+impl<T: VariantValue> fmt::Display for Filter<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{expr}", expr = self.0)
     }
 }
 
-impl Queryable for Filter {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Filter<T> {
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        let ctx = ctx.with_parent(current);
         if let Some(list) = current.as_array() {
             list.iter()
-                .filter(|v| self.0.test_filter(*v, root, registry))
+                .enumerate()
+                .filter(|(i, v)| self.0.test_filter(*v, ctx.with_key(*i)))
+                .map(|(_, v)| v)
                 .collect()
         } else if let Some(obj) = current.as_object() {
             obj.iter()
+                .filter(|(k, v)| self.0.test_filter(*v, ctx.with_key(*k)))
                 .map(|(_, v)| v)
-                .filter(|v| self.0.test_filter(*v, root, registry))
                 .collect()
         } else {
             vec![]
         }
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'_, 'b, T>,
         parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
+        let ctx = ctx.with_parent(current);
         if let Some(list) = current.as_array() {
             list.iter()
                 .enumerate()
-                .filter(|(_, v)| self.0.test_filter(*v, root, registry))
+                .filter(|(i, v)| self.0.test_filter(*v, ctx.with_key(*i)))
                 .map(|(i, v)| LocatedNode::new(parent.clone_and_push(i), v))
                 .collect()
         } else if let Some(obj) = current.as_object() {
             obj.iter()
-                .filter(|(_, v)| self.0.test_filter(*v, root, registry))
+                .filter(|(k, v)| self.0.test_filter(*v, ctx.with_key(*k)))
                 .map(|(k, v)| LocatedNode::new(parent.clone_and_push(k), v))
                 .collect()
         } else {
@@ -122,9 +121,9 @@ impl Queryable for Filter {
 /// This is also `logical-expression` in RFC 9535, but the naming was chosen to
 /// make it more clear that it represents the logical OR, and to not have an extra wrapping type.
 #[derive(Debug, Clone)]
-pub struct LogicalOrExpr(pub Vec<LogicalAndExpr>);
+pub struct LogicalOrExpr<T: VariantValue>(pub Vec<LogicalAndExpr<T>>);
 
-impl fmt::Display for LogicalOrExpr {
+impl<T: VariantValue> fmt::Display for LogicalOrExpr<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, expr) in self.0.iter().enumerate() {
             write!(
@@ -137,24 +136,17 @@ impl fmt::Display for LogicalOrExpr {
     }
 }
 
-impl TestFilter for LogicalOrExpr {
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool {
-        self.0
-            .iter()
-            .any(|expr| expr.test_filter(current, root, registry))
+impl<T: VariantValue> TestFilter<T> for LogicalOrExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
+        self.0.iter().any(|expr| expr.test_filter(current, ctx))
     }
 }
 
 /// A logical AND expression.
 #[derive(Debug, Clone)]
-pub struct LogicalAndExpr(pub Vec<BasicExpr>);
+pub struct LogicalAndExpr<T: VariantValue>(pub Vec<BasicExpr<T>>);
 
-impl fmt::Display for LogicalAndExpr {
+impl<T: VariantValue> fmt::Display for LogicalAndExpr<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, expr) in self.0.iter().enumerate() {
             write!(
@@ -167,39 +159,35 @@ impl fmt::Display for LogicalAndExpr {
     }
 }
 
-impl TestFilter for LogicalAndExpr {
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool {
-        self.0
-            .iter()
-            .all(|expr| expr.test_filter(current, root, registry))
+impl<T: VariantValue> TestFilter<T> for LogicalAndExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
+        self.0.iter().all(|expr| expr.test_filter(current, ctx))
     }
 }
 
 /// The basic for m of expression in a filter.
 #[derive(Debug, Clone)]
-pub enum BasicExpr {
+pub enum BasicExpr<T: VariantValue> {
     /// An expression wrapped in parentheses.
-    Paren(LogicalOrExpr),
+    Paren(LogicalOrExpr<T>),
     /// A parenthesized expression preceded with a `!`.
-    ParenNot(LogicalOrExpr),
+    ParenNot(LogicalOrExpr<T>),
     /// A relationship expression which compares two variant values.
-    Relation(ComparisonExpr),
+    Relation(ComparisonExpr<T>),
     /// An existence expression.
-    Exist(ExistExpr),
+    Exist(ExistExpr<T>),
     /// The inverse of an existence expression, i.e., preceded by `!`.
-    NotExist(ExistExpr),
+    NotExist(ExistExpr<T>),
     /// A function expression.
-    FuncExpr(FunctionExpr),
+    FuncExpr(FunctionExpr<T>),
     /// The inverse of a function expression, i.e., preceded by `!`.
-    FuncNotExpr(FunctionExpr),
+    FuncNotExpr(FunctionExpr<T>),
+    /// A regex match expression, i.e., with `=~` or `!~`.
+    #[cfg(feature = "regex")]
+    Match(MatchExpr<T>),
 }
 
-impl fmt::Display for BasicExpr {
+impl<T: VariantValue> fmt::Display for BasicExpr<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             BasicExpr::Paren(expr) => write!(f, "({expr})"),
@@ -209,13 +197,15 @@ impl fmt::Display for BasicExpr {
             BasicExpr::NotExist(exist) => write!(f, "!{exist}"),
             BasicExpr::FuncExpr(expr) => write!(f, "{expr}"),
             BasicExpr::FuncNotExpr(expr) => write!(f, "!{expr}"),
+            #[cfg(feature = "regex")]
+            BasicExpr::Match(expr) => write!(f, "{expr}"),
         }
     }
 }
 
-impl BasicExpr {
+impl<T: VariantValue> BasicExpr<T> {
     /// Optionally express as a relation expression
-    pub fn as_relation(&self) -> Option<&ComparisonExpr> {
+    pub fn as_relation(&self) -> Option<&ComparisonExpr<T>> {
         match self {
             BasicExpr::Relation(cx) => Some(cx),
             _ => None,
@@ -223,62 +213,56 @@ impl BasicExpr {
     }
 }
 
-impl TestFilter for BasicExpr {
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool {
+impl<T: VariantValue> TestFilter<T> for BasicExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
         match self {
-            BasicExpr::Paren(expr) => expr.test_filter(current, root, registry),
-            BasicExpr::ParenNot(expr) => !expr.test_filter(current, root, registry),
-            BasicExpr::Relation(expr) => expr.test_filter(current, root, registry),
-            BasicExpr::Exist(expr) => expr.test_filter(current, root, registry),
-            BasicExpr::NotExist(expr) => !expr.test_filter(current, root, registry),
-            BasicExpr::FuncExpr(expr) => expr.test_filter(current, root, registry),
-            BasicExpr::FuncNotExpr(expr) => !expr.test_filter(current, root, registry),
+            BasicExpr::Paren(expr) => expr.test_filter(current, ctx),
+            BasicExpr::ParenNot(expr) => !expr.test_filter(current, ctx),
+            BasicExpr::Relation(expr) => expr.test_filter(current, ctx),
+            BasicExpr::Exist(expr) => expr.test_filter(current, ctx),
+            BasicExpr::NotExist(expr) => !expr.test_filter(current, ctx),
+            BasicExpr::FuncExpr(expr) => expr.test_filter(current, ctx),
+            BasicExpr::FuncNotExpr(expr) => !expr.test_filter(current, ctx),
+            #[cfg(feature = "regex")]
+            BasicExpr::Match(expr) => expr.test_filter(current, ctx),
         }
     }
 }
 
 /// Existence expression.
 #[derive(Debug, Clone)]
-pub struct ExistExpr(pub Query);
+pub struct ExistExpr<T: VariantValue>(pub Query<T>);
 
-impl fmt::Display for ExistExpr {
+impl<T: VariantValue> fmt::Display for ExistExpr<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{query}", query = self.0)
     }
 }
 
-impl TestFilter for ExistExpr {
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool {
-        !self.0.query(current, root, registry).is_empty()
+impl<T: VariantValue> TestFilter<T> for ExistExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
+        !self.0.query(current, ctx).is_empty()
     }
 }
 
 /// A comparison expression comparing two variant values
 #[derive(Debug, Clone)]
-pub struct ComparisonExpr {
+pub struct ComparisonExpr<T: VariantValue> {
     /// The variant value on the left of the comparison
-    pub left: Comparable,
+    pub left: ArithmeticExpr<T>,
     /// The operator of comparison
     pub op: ComparisonOperator,
     /// The variant value on the right of the comparison
-    pub right: Comparable,
+    pub right: ArithmeticExpr<T>,
 }
 
-impl fmt::Display for ComparisonExpr {
+impl<T: VariantValue> fmt::Display for ComparisonExpr<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // keep membership operators apart from their operands, e.g., `$$x in $$y`
+        let space = if self.op.is_membership() { " " } else { "" };
         write!(
             f,
-            "{left}{op}{right}",
+            "{left}{space}{op}{space}{right}",
             left = self.left,
             op = self.op,
             right = self.right
@@ -286,15 +270,10 @@ impl fmt::Display for ComparisonExpr {
     }
 }
 
-impl TestFilter for ComparisonExpr {
-    fn test_filter<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> bool {
-        let left = self.left.as_value(current, root, registry);
-        let right = self.right.as_value(current, root, registry);
+impl<T: VariantValue> TestFilter<T> for ComparisonExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
+        let left = self.left.as_value(current, ctx);
+        let right = self.right.as_value(current, ctx);
         match self.op {
             ComparisonOperator::EqualTo => check_equal_to(&left, &right),
             ComparisonOperator::NotEqualTo => !check_equal_to(&left, &right),
@@ -306,6 +285,9 @@ impl TestFilter for ComparisonExpr {
             ComparisonOperator::GreaterThanEqualTo => {
                 check_less_than(&right, &left) || check_equal_to(&left, &right)
             }
+            ComparisonOperator::In => check_member_of(&left, &right),
+            ComparisonOperator::NotIn => !check_member_of(&left, &right),
+            ComparisonOperator::Contains => check_member_of(&right, &left),
         }
     }
 }
@@ -336,6 +318,97 @@ fn check_less_than<T: VariantValue>(left: &SPathValue<T>, right: &SPathValue<T>)
     left.is_less_than(right)
 }
 
+/// A regex match expression, i.e., `left =~ /pattern/flags` or `left !~ /pattern/flags`.
+///
+/// This is syntactic sugar lowered onto the `search` function, e.g., `@.name =~ /foo.*/i` is
+/// evaluated as `search(@.name, '(?i)foo.*')`.
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+pub struct MatchExpr<T: VariantValue> {
+    /// The regex literal on the right of the operator
+    pub regex: RegexLiteral,
+    /// Whether the match is inverted, i.e., with `!~`
+    pub negated: bool,
+    /// The lowered `search` function expression, whose first argument is the matched value
+    pub search: FunctionExpr<T>,
+}
+
+#[cfg(feature = "regex")]
+impl<T: VariantValue> fmt::Display for MatchExpr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = if self.negated { "!~" } else { "=~" };
+        write!(
+            f,
+            "{left}{op}{regex}",
+            left = self.search.args[0],
+            regex = self.regex
+        )
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<T: VariantValue> TestFilter<T> for MatchExpr<T> {
+    fn test_filter<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> bool {
+        self.search.test_filter(current, ctx) != self.negated
+    }
+}
+
+/// A regex literal, i.e., `/pattern/flags`.
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexLiteral {
+    /// The pattern between the slashes, as written
+    pub pattern: String,
+    /// The flags after the closing slash, any of `i`, `m` and `s`
+    pub flags: String,
+}
+
+#[cfg(feature = "regex")]
+impl RegexLiteral {
+    /// The pattern with its flags inlined, e.g., `(?i)foo` for `/foo/i`.
+    pub fn to_pattern(&self) -> String {
+        if self.flags.is_empty() {
+            self.pattern.clone()
+        } else {
+            format!(
+                "(?{flags}){pattern}",
+                flags = self.flags,
+                pattern = self.pattern
+            )
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+impl fmt::Display for RegexLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "/{pattern}/{flags}",
+            pattern = self.pattern,
+            flags = self.flags
+        )
+    }
+}
+
+/// Check whether `item` equals to any element of the `array`.
+///
+/// This is false if `item` is nothing or `array` is not an array.
+fn check_member_of<T: VariantValue>(item: &SPathValue<T>, array: &SPathValue<T>) -> bool {
+    let item = match item {
+        SPathValue::Node(v) => *v,
+        SPathValue::Value(v) => v,
+        _ => return false,
+    };
+    let array = match array {
+        SPathValue::Node(v) => v.as_array(),
+        SPathValue::Value(v) => v.as_array(),
+        _ => None,
+    };
+
+    array.is_some_and(|array| array.iter().any(|v| item.is_equal_to(v)))
+}
+
 /// The comparison operator
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ComparisonOperator {
@@ -351,6 +424,22 @@ pub enum ComparisonOperator {
     LessThanEqualTo,
     /// `>=`
     GreaterThanEqualTo,
+    /// `in`, whether the left value is an element of the right array
+    In,
+    /// `nin`, the negation of `in`
+    NotIn,
+    /// `contains`, whether the left array has the right value as an element
+    Contains,
+}
+
+impl ComparisonOperator {
+    /// Whether this is a membership operator, i.e., `in`, `nin` or `contains`.
+    pub fn is_membership(&self) -> bool {
+        matches!(
+            self,
+            ComparisonOperator::In | ComparisonOperator::NotIn | ComparisonOperator::Contains
+        )
+    }
 }
 
 impl fmt::Display for ComparisonOperator {
@@ -362,13 +451,145 @@ impl fmt::Display for ComparisonOperator {
             ComparisonOperator::GreaterThan => write!(f, ">"),
             ComparisonOperator::LessThanEqualTo => write!(f, "<="),
             ComparisonOperator::GreaterThanEqualTo => write!(f, ">="),
+            ComparisonOperator::In => write!(f, "in"),
+            ComparisonOperator::NotIn => write!(f, "nin"),
+            ComparisonOperator::Contains => write!(f, "contains"),
+        }
+    }
+}
+
+/// An arithmetic expression over comparables, e.g., `@.price * @.qty`.
+///
+/// A plain comparable is the trivial arithmetic expression.
+#[derive(Debug, Clone)]
+pub enum ArithmeticExpr<T: VariantValue> {
+    /// A comparable operand.
+    Comparable(Comparable<T>),
+    /// A binary arithmetic operation.
+    Binary {
+        /// The left operand
+        left: Box<ArithmeticExpr<T>>,
+        /// The arithmetic operator
+        op: ArithmeticOperator,
+        /// The right operand
+        right: Box<ArithmeticExpr<T>>,
+    },
+}
+
+impl<T: VariantValue> ArithmeticExpr<T> {
+    /// Evaluate the expression to a variant value.
+    ///
+    /// An operation evaluates to nothing if any operand is not a number, or if the result is not
+    /// representable, e.g., on integer overflow or division by zero.
+    pub fn as_value<'a, 'b: 'a>(
+        &'a self,
+        current: &'b T,
+        ctx: EvalContext<'a, 'b, T>,
+    ) -> SPathValue<'a, T> {
+        match self {
+            ArithmeticExpr::Comparable(comparable) => comparable.as_value(current, ctx),
+            ArithmeticExpr::Binary { left, op, right } => {
+                let left = left.as_value(current, ctx);
+                let right = right.as_value(current, ctx);
+                let result = match (as_number(&left), as_number(&right)) {
+                    (Some(l), Some(r)) => op.apply(l, r),
+                    _ => None,
+                };
+                match result.and_then(Number::into_value) {
+                    Some(v) => SPathValue::Value(v),
+                    None => SPathValue::Nothing,
+                }
+            }
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            ArithmeticExpr::Comparable(_) => u8::MAX,
+            ArithmeticExpr::Binary { op, .. } => op.precedence(),
+        }
+    }
+}
+
+fn as_number<T: VariantValue>(value: &SPathValue<T>) -> Option<Number> {
+    match value {
+        SPathValue::Node(v) => Number::from_value(*v),
+        SPathValue::Value(v) => Number::from_value(v),
+        _ => None,
+    }
+}
+
+impl<T: VariantValue> fmt::Display for ArithmeticExpr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticExpr::Comparable(comparable) => write!(f, "{comparable}"),
+            ArithmeticExpr::Binary { left, op, right } => {
+                // operators are left-associative
+                if left.precedence() < op.precedence() {
+                    write!(f, "({left})")?;
+                } else {
+                    write!(f, "{left}")?;
+                }
+                write!(f, "{op}")?;
+                if right.precedence() <= op.precedence() {
+                    write!(f, "({right})")
+                } else {
+                    write!(f, "{right}")
+                }
+            }
+        }
+    }
+}
+
+/// The arithmetic operator
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArithmeticOperator {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+}
+
+impl ArithmeticOperator {
+    fn precedence(&self) -> u8 {
+        match self {
+            ArithmeticOperator::Add | ArithmeticOperator::Sub => 1,
+            ArithmeticOperator::Mul | ArithmeticOperator::Div | ArithmeticOperator::Rem => 2,
+        }
+    }
+
+    fn apply(&self, left: Number, right: Number) -> Option<Number> {
+        match self {
+            ArithmeticOperator::Add => left.add(right),
+            ArithmeticOperator::Sub => left.sub(right),
+            ArithmeticOperator::Mul => left.mul(right),
+            ArithmeticOperator::Div => left.div(right),
+            ArithmeticOperator::Rem => left.rem(right),
+        }
+    }
+}
+
+impl fmt::Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticOperator::Add => write!(f, "+"),
+            ArithmeticOperator::Sub => write!(f, "-"),
+            ArithmeticOperator::Mul => write!(f, "*"),
+            ArithmeticOperator::Div => write!(f, "/"),
+            ArithmeticOperator::Rem => write!(f, "%"),
         }
     }
 }
 
 /// A type that is comparable
 #[derive(Debug, Clone)]
-pub enum Comparable {
+pub enum Comparable<T: VariantValue> {
     /// A literal variant value, excluding objects and arrays.
     Literal(Literal),
     /// A singular query.
@@ -376,41 +597,66 @@ pub enum Comparable {
     /// This will only produce a single node, i.e., a variant value, or nothing
     SingularQuery(SingularQuery),
     /// A function expression that can only produce a `ValueType`
-    FunctionExpr(FunctionExpr),
+    FunctionExpr(FunctionExpr<T>),
+    /// A named parameter `$$name` bound at evaluation time.
+    Parameter(String),
+    /// A `let` binding `$name` of the main query.
+    Binding(String),
+    /// The member name (or array index) of the current filter candidate, i.e., `@key`.
+    Key,
 }
 
-impl fmt::Display for Comparable {
+impl<T: VariantValue> fmt::Display for Comparable<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Comparable::Literal(lit) => write!(f, "{lit}"),
             Comparable::SingularQuery(path) => write!(f, "{path}"),
             Comparable::FunctionExpr(expr) => write!(f, "{expr}"),
+            Comparable::Parameter(name) => write!(f, "$${name}"),
+            Comparable::Binding(name) => write!(f, "${name}"),
+            Comparable::Key => write!(f, "@key"),
         }
     }
 }
 
-impl Comparable {
+impl<T: VariantValue> Comparable<T> {
     /// Convert the comparable variable to a variant value.
-    pub fn as_value<'a, 'b: 'a, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    pub fn as_value<'a, 'b: 'a>(
         &'a self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'a, 'b, T>,
     ) -> SPathValue<'a, T> {
         match self {
             Comparable::Literal(lit) => match T::from_literal(lit.clone()) {
                 Some(v) => SPathValue::Value(v),
                 None => SPathValue::Nothing,
             },
-            Comparable::SingularQuery(sp) => match sp.eval_query(current, root) {
+            Comparable::SingularQuery(sp) => match sp.eval_query(current, ctx) {
                 Some(v) => SPathValue::Node(v),
                 None => SPathValue::Nothing,
             },
-            Comparable::FunctionExpr(expr) => expr.evaluate(current, root, registry),
+            Comparable::FunctionExpr(expr) => expr.evaluate(current, ctx),
+            Comparable::Parameter(name) => match ctx.param(name) {
+                Some(v) => SPathValue::Node(v),
+                None => SPathValue::Nothing,
+            },
+            Comparable::Binding(name) => match ctx.binding(name) {
+                Some(v) => SPathValue::Node(v),
+                None => SPathValue::Nothing,
+            },
+            Comparable::Key => eval_key(ctx),
         }
     }
 }
 
+/// Evaluate `@key`, i.e., the member name (or array index) of the current filter candidate.
+pub(crate) fn eval_key<'a, T: VariantValue>(ctx: EvalContext<'_, '_, T>) -> SPathValue<'a, T> {
+    match ctx.key().and_then(|key| T::from_literal(key.to_literal())) {
+        Some(v) => SPathValue::Value(v),
+        None => SPathValue::Nothing,
+    }
+}
+
 /// A segment in a singular query
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum SingularQuerySegment {
@@ -429,10 +675,10 @@ impl fmt::Display for SingularQuerySegment {
     }
 }
 
-impl TryFrom<QuerySegment> for SingularQuerySegment {
+impl<T: VariantValue> TryFrom<QuerySegment<T>> for SingularQuerySegment {
     type Error = NonSingularQueryError;
 
-    fn try_from(segment: QuerySegment) -> Result<Self, Self::Error> {
+    fn try_from(segment: QuerySegment<T>) -> Result<Self, Self::Error> {
         if segment.is_descendent() {
             return Err(NonSingularQueryError::Descendant);
         }
@@ -448,20 +694,22 @@ impl TryFrom<QuerySegment> for SingularQuerySegment {
             }
             Segment::DotName(name) => Ok(Self::Name(Name::new(name))),
             Segment::Wildcard => Err(NonSingularQueryError::Wildcard),
+            Segment::Parent => Err(NonSingularQueryError::Parent),
         }
     }
 }
 
-impl TryFrom<Selector> for SingularQuerySegment {
+impl<T: VariantValue> TryFrom<Selector<T>> for SingularQuerySegment {
     type Error = NonSingularQueryError;
 
-    fn try_from(selector: Selector) -> Result<Self, Self::Error> {
+    fn try_from(selector: Selector<T>) -> Result<Self, Self::Error> {
         match selector {
             Selector::Name(n) => Ok(Self::Name(n)),
             Selector::Wildcard => Err(NonSingularQueryError::Wildcard),
             Selector::Index(i) => Ok(Self::Index(i)),
             Selector::ArraySlice(_) => Err(NonSingularQueryError::Slice),
             Selector::Filter(_) => Err(NonSingularQueryError::Filter),
+            Selector::Sort(_) => Err(NonSingularQueryError::Sort),
         }
     }
 }
@@ -477,29 +725,25 @@ pub struct SingularQuery {
 
 impl SingularQuery {
     /// Evaluate the singular query
-    pub fn eval_query<'b, T: VariantValue>(&self, current: &'b T, root: &'b T) -> Option<&'b T> {
+    pub fn eval_query<'b, T: VariantValue>(
+        &self,
+        current: &'b T,
+        ctx: EvalContext<'_, 'b, T>,
+    ) -> Option<&'b T> {
         let mut target = match self.kind {
-            SingularQueryKind::Absolute => root,
+            SingularQueryKind::Absolute => ctx.root(),
             SingularQueryKind::Relative => current,
+            SingularQueryKind::Parent => ctx.parent()?,
         };
         for segment in &self.segments {
             match segment {
                 SingularQuerySegment::Name(name) => {
-                    if let Some(t) = target.as_object().and_then(|o| o.get(name.as_str())) {
-                        target = t;
-                    } else {
-                        return None;
-                    }
+                    target = target.as_object().and_then(|o| o.get(name.as_str()))?;
                 }
                 SingularQuerySegment::Index(i) => {
-                    if let Some(t) = target
+                    target = target
                         .as_array()
-                        .and_then(|l| usize::try_from(i.index()).ok().and_then(|i| l.get(i)))
-                    {
-                        target = t;
-                    } else {
-                        return None;
-                    }
+                        .and_then(|l| usize::try_from(i.index()).ok().and_then(|i| l.get(i)))?;
                 }
             }
         }
@@ -507,10 +751,10 @@ impl SingularQuery {
     }
 }
 
-impl TryFrom<Query> for SingularQuery {
+impl<T: VariantValue> TryFrom<Query<T>> for SingularQuery {
     type Error = NonSingularQueryError;
 
-    fn try_from(query: Query) -> Result<Self, Self::Error> {
+    fn try_from(query: Query<T>) -> Result<Self, Self::Error> {
         let kind = SingularQueryKind::from(query.kind);
         let segments = query
             .segments
@@ -526,6 +770,7 @@ impl fmt::Display for SingularQuery {
         match self.kind {
             SingularQueryKind::Absolute => write!(f, "$")?,
             SingularQueryKind::Relative => write!(f, "@")?,
+            SingularQueryKind::Parent => write!(f, "@^")?,
         }
         for s in &self.segments {
             write!(f, "[{s}]")?;
@@ -541,6 +786,8 @@ pub enum SingularQueryKind {
     Absolute,
     /// Referencing the current node, i.e., `@`
     Relative,
+    /// Referencing the parent of the current node within a filter, i.e., `@^`
+    Parent,
 }
 
 impl From<QueryKind> for SingularQueryKind {
@@ -548,12 +795,13 @@ impl From<QueryKind> for SingularQueryKind {
         match qk {
             QueryKind::Root => Self::Absolute,
             QueryKind::Current => Self::Relative,
+            QueryKind::Parent => Self::Parent,
         }
     }
 }
 
 /// Error when parsing a singular query
-#[derive(Debug, thiserror::Error, PartialEq)]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
 pub enum NonSingularQueryError {
     /// Descendant segment
     #[error("descendant segments are not singular")]
@@ -573,4 +821,10 @@ pub enum NonSingularQueryError {
     /// A filter segment
     #[error("filter segments are not singular")]
     Filter,
+    /// A parent segment
+    #[error("parent segments are not allowed in singular queries")]
+    Parent,
+    /// A sort segment
+    #[error("sort segments are not singular")]
+    Sort,
 }
@@ -18,7 +18,7 @@ use std::fmt;
 
 use num_traits::ToPrimitive;
 
-use crate::spec::function::FunctionRegistry;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Queryable;
 use crate::ConcreteVariantArray;
 use crate::LocatedNode;
@@ -70,13 +70,8 @@ fn resolve_index(index: i64, len: usize) -> Option<usize> {
     }
 }
 
-impl Queryable for Index {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        _root: &'b T,
-        _registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Index {
+    fn query<'b>(&self, current: &'b T, _ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
         current
             .as_array()
             .and_then(|list| {
@@ -87,11 +82,10 @@ impl Queryable for Index {
             .unwrap_or_default()
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        _root: &'b T,
-        _registry: &Registry,
+        _ctx: EvalContext<'_, 'b, T>,
         mut parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
         current
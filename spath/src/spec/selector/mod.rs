@@ -18,13 +18,15 @@ pub mod filter;
 pub mod index;
 pub mod name;
 pub mod slice;
+pub mod sort;
 
 use std::fmt;
 
 use self::index::Index;
 use self::name::Name;
 use self::slice::Slice;
-use crate::spec::function::FunctionRegistry;
+use self::sort::Sort;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Queryable;
 use crate::spec::select_wildcard;
 use crate::spec::selector::filter::Filter;
@@ -36,7 +38,7 @@ use crate::VariantValue;
 
 /// An SPath selector
 #[derive(Debug, Clone)]
-pub enum Selector {
+pub enum Selector<T: VariantValue> {
     /// Select an object key
     Name(Name),
     /// Select all nodes
@@ -49,17 +51,19 @@ pub enum Selector {
     /// Select a slice from an array
     ArraySlice(Slice),
     /// Use a filter to select nodes
-    Filter(Filter),
+    Filter(Filter<T>),
+    /// Select the elements of an array in order, e.g., `sort(@.price desc)`
+    Sort(Sort),
 }
 
-impl Selector {
+impl<T: VariantValue> Selector<T> {
     /// Whether this selector selects at most a single node.
     pub fn is_singular(&self) -> bool {
         matches!(self, Selector::Name(_) | Selector::Index(_))
     }
 }
 
-impl fmt::Display for Selector {
+impl<T: VariantValue> fmt::Display for Selector<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Selector::Name(name) => write!(f, "{name}"),
@@ -67,37 +71,33 @@ impl fmt::Display for Selector {
             Selector::Index(index) => write!(f, "{index}"),
             Selector::ArraySlice(slice) => write!(f, "{slice}"),
             Selector::Filter(filter) => write!(f, "?{filter}"),
+            Selector::Sort(sort) => write!(f, "{sort}"),
         }
     }
 }
 
-impl Queryable for Selector {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        root: &'b T,
-        registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Selector<T> {
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
         let mut result = Vec::new();
         match self {
-            Selector::Name(name) => result.append(&mut name.query(current, root, registry)),
+            Selector::Name(name) => result.append(&mut name.query(current, ctx)),
             Selector::Wildcard => select_wildcard(&mut result, current),
-            Selector::Index(index) => result.append(&mut index.query(current, root, registry)),
-            Selector::ArraySlice(slice) => result.append(&mut slice.query(current, root, registry)),
-            Selector::Filter(filter) => result.append(&mut filter.query(current, root, registry)),
+            Selector::Index(index) => result.append(&mut index.query(current, ctx)),
+            Selector::ArraySlice(slice) => result.append(&mut slice.query(current, ctx)),
+            Selector::Filter(filter) => result.append(&mut filter.query(current, ctx)),
+            Selector::Sort(sort) => result.append(&mut sort.query(current, ctx)),
         }
         result
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        root: &'b T,
-        registry: &Registry,
+        ctx: EvalContext<'_, 'b, T>,
         parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
         match self {
-            Selector::Name(name) => name.query_located(current, root, registry, parent),
+            Selector::Name(name) => name.query_located(current, ctx, parent),
             Selector::Wildcard => {
                 if let Some(list) = current.as_array() {
                     list.iter()
@@ -112,9 +112,10 @@ impl Queryable for Selector {
                     vec![]
                 }
             }
-            Selector::Index(index) => index.query_located(current, root, registry, parent),
-            Selector::ArraySlice(slice) => slice.query_located(current, root, registry, parent),
-            Selector::Filter(filter) => filter.query_located(current, root, registry, parent),
+            Selector::Index(index) => index.query_located(current, ctx, parent),
+            Selector::ArraySlice(slice) => slice.query_located(current, ctx, parent),
+            Selector::Filter(filter) => filter.query_located(current, ctx, parent),
+            Selector::Sort(sort) => sort.query_located(current, ctx, parent),
         }
     }
 }
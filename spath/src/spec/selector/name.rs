@@ -16,7 +16,7 @@
 
 use std::fmt;
 
-use crate::spec::function::FunctionRegistry;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Queryable;
 use crate::ConcreteVariantObject;
 use crate::LocatedNode;
@@ -49,13 +49,8 @@ impl fmt::Display for Name {
     }
 }
 
-impl Queryable for Name {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        _root: &'b T,
-        _registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Name {
+    fn query<'b>(&self, current: &'b T, _ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
         let name = self.name.as_str();
         current
             .as_object()
@@ -64,11 +59,10 @@ impl Queryable for Name {
             .unwrap_or_default()
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        _root: &'b T,
-        _registry: &Registry,
+        _ctx: EvalContext<'_, 'b, T>,
         mut parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
         let name = self.name.as_str();
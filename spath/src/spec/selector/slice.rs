@@ -20,7 +20,7 @@ use std::cmp::Ordering;
 
 use num_traits::ToPrimitive;
 
-use crate::spec::function::FunctionRegistry;
+use crate::spec::query::EvalContext;
 use crate::spec::query::Queryable;
 use crate::ConcreteVariantArray;
 use crate::LocatedNode;
@@ -111,6 +111,14 @@ impl Slice {
             None => return vec![],
         };
 
+        self.positions(vec.len())
+            .into_iter()
+            .map(|i| make_node(i, vec.get(i).unwrap()))
+            .collect()
+    }
+
+    /// The positions selected by this slice from a sequence of `len` elements, in order.
+    pub(crate) fn positions(&self, len: usize) -> Vec<usize> {
         let (start, end, step) = (self.start, self.end, self.step.unwrap_or(1));
         if step == 0 {
             // §2.3.4.2.2. Normative Semantics
@@ -118,7 +126,7 @@ impl Slice {
             return vec![];
         }
 
-        let len = vec.len().to_i64().unwrap_or(i64::MAX);
+        let len = len.to_i64().unwrap_or(i64::MAX);
         let (start, end) = if step >= 0 {
             match (start, end) {
                 (Some(start), Some(end)) => (start, end),
@@ -142,8 +150,7 @@ impl Slice {
                 // step > 0
                 let mut i = lower;
                 while i < upper {
-                    let node = vec.get(i as usize).unwrap();
-                    selected.push(make_node(i as usize, node));
+                    selected.push(i as usize);
                     i += step;
                 }
             }
@@ -151,8 +158,7 @@ impl Slice {
                 // step < 0
                 let mut i = upper;
                 while lower < i {
-                    let node = vec.get(i as usize).unwrap();
-                    selected.push(make_node(i as usize, node));
+                    selected.push(i as usize);
                     i += step;
                 }
             }
@@ -162,21 +168,15 @@ impl Slice {
     }
 }
 
-impl Queryable for Slice {
-    fn query<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
-        &self,
-        current: &'b T,
-        _root: &'b T,
-        _registry: &Registry,
-    ) -> Vec<&'b T> {
+impl<T: VariantValue> Queryable<T> for Slice {
+    fn query<'b>(&self, current: &'b T, _ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
         self.select(current, |_, node| node)
     }
 
-    fn query_located<'b, T: VariantValue, Registry: FunctionRegistry<Value = T>>(
+    fn query_located<'b>(
         &self,
         current: &'b T,
-        _root: &'b T,
-        _registry: &Registry,
+        _ctx: EvalContext<'_, 'b, T>,
         parent: NormalizedPath<'b>,
     ) -> Vec<LocatedNode<'b, T>> {
         self.select(current, |i, node| {
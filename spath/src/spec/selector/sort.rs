@@ -0,0 +1,223 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sort selectors for ordering array elements in SPath.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::spec::query::EvalContext;
+use crate::spec::query::Queryable;
+use crate::spec::selector::filter::SingularQuery;
+use crate::spec::selector::slice::Slice;
+use crate::ConcreteVariantArray;
+use crate::LocatedNode;
+use crate::NormalizedPath;
+use crate::VariantValue;
+
+/// The sort selector, e.g., `sort(@.price desc, @.title)[:3]`.
+///
+/// Selects the elements of an array ordered by the sort keys, optionally followed by a slice of
+/// the ordered elements. This is an extension beyond RFC 9535.
+///
+/// The order is stable, and is defined as follows for the values of a sort key:
+///
+/// * Values are compared with [`VariantValue::is_less_than`].
+/// * Values of different types are ordered by type: null, booleans, numbers, NaN, strings, arrays
+///   and then objects; values of the same type that are not comparable are equal.
+/// * Nothing, i.e., the key query selects no node, is always placed last, even in descending order.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Sort {
+    /// The sort keys, in order of precedence.
+    keys: Vec<SortKey>,
+    /// The slice of the ordered elements to select, if any.
+    slice: Option<Slice>,
+}
+
+impl Sort {
+    /// Create a new sort selector.
+    pub fn new(keys: Vec<SortKey>, slice: Option<Slice>) -> Self {
+        Self { keys, slice }
+    }
+
+    /// Get the sort keys of the selector.
+    pub fn keys(&self) -> &[SortKey] {
+        &self.keys
+    }
+
+    /// Get the slice of the ordered elements, if any.
+    pub fn slice(&self) -> Option<&Slice> {
+        self.slice.as_ref()
+    }
+
+    fn select<'b, T, N, F>(
+        &self,
+        current: &'b T,
+        ctx: EvalContext<'_, 'b, T>,
+        make_node: F,
+    ) -> Vec<N>
+    where
+        T: VariantValue,
+        N: 'b,
+        F: Fn(usize, &'b T) -> N,
+    {
+        let list = match current.as_array() {
+            Some(list) => list,
+            None => return vec![],
+        };
+
+        // sort keys are evaluated like filters, with each element as the candidate
+        let ctx = ctx.with_parent(current);
+        let mut elements = list
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let keys = self
+                    .keys
+                    .iter()
+                    .map(|key| key.query.eval_query(node, ctx.with_key(i)))
+                    .collect::<Vec<_>>();
+                (i, node, keys)
+            })
+            .collect::<Vec<_>>();
+        // `sort_by` is stable
+        elements.sort_by(|(_, _, a), (_, _, b)| {
+            self.keys
+                .iter()
+                .zip(a.iter().zip(b.iter()))
+                .map(|(key, (a, b))| compare_keys(*a, *b, key.descending))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        match self.slice {
+            None => elements
+                .into_iter()
+                .map(|(i, node, _)| make_node(i, node))
+                .collect(),
+            Some(slice) => slice
+                .positions(elements.len())
+                .into_iter()
+                .map(|pos| {
+                    let (i, node, _) = &elements[pos];
+                    make_node(*i, *node)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sort(")?;
+        for (i, key) in self.keys.iter().enumerate() {
+            write!(
+                f,
+                "{key}{comma}",
+                comma = if i == self.keys.len() - 1 { "" } else { "," }
+            )?;
+        }
+        write!(f, ")")?;
+        if let Some(slice) = &self.slice {
+            write!(f, "[{slice}]")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: VariantValue> Queryable<T> for Sort {
+    fn query<'b>(&self, current: &'b T, ctx: EvalContext<'_, 'b, T>) -> Vec<&'b T> {
+        self.select(current, ctx, |_, node| node)
+    }
+
+    fn query_located<'b>(
+        &self,
+        current: &'b T,
+        ctx: EvalContext<'_, 'b, T>,
+        parent: NormalizedPath<'b>,
+    ) -> Vec<LocatedNode<'b, T>> {
+        self.select(current, ctx, |i, node| {
+            LocatedNode::new(parent.clone_and_push(i), node)
+        })
+    }
+}
+
+/// A sort key of the [`Sort`] selector, i.e., a singular query with an optional direction.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SortKey {
+    /// The singular query evaluated against each element.
+    pub query: SingularQuery,
+    /// Whether to sort in descending order, i.e., with `desc`.
+    pub descending: bool,
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{query}", query = self.query)?;
+        if self.descending {
+            write!(f, " desc")?;
+        }
+        Ok(())
+    }
+}
+
+fn compare_keys<T: VariantValue>(a: Option<&T>, b: Option<&T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        // nothing is placed last regardless of the direction
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let ordering = compare_values(a, b);
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+    }
+}
+
+fn compare_values<T: VariantValue>(a: &T, b: &T) -> Ordering {
+    // a total order is required by sorting; rank by types first, and NaN after numbers
+    fn rank<T: VariantValue>(v: &T) -> u8 {
+        if v.is_null() {
+            0
+        } else if v.is_boolean() {
+            1
+        } else if v.is_number() {
+            if v.as_f64().is_some_and(f64::is_nan) {
+                3
+            } else {
+                2
+            }
+        } else if v.is_string() {
+            4
+        } else if v.is_array() {
+            5
+        } else {
+            6
+        }
+    }
+
+    rank(a).cmp(&rank(b)).then_with(|| {
+        if a.is_less_than(b) {
+            Ordering::Less
+        } else if b.is_less_than(a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    })
+}
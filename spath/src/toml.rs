@@ -19,11 +19,13 @@ use toml::Value;
 use crate::spec::function;
 use crate::value::ConcreteVariantArray;
 use crate::value::ConcreteVariantObject;
+use crate::value::VariantBuilder;
 use crate::value::VariantValue;
 use crate::FromLiteral;
 use crate::Literal;
 
 pub type BuiltinFunctionRegistry = function::BuiltinFunctionRegistry<Value>;
+pub type CustomFunctionRegistry = function::CustomFunctionRegistry<Value>;
 
 impl FromLiteral for Value {
     fn from_literal(literal: Literal) -> Option<Self> {
@@ -33,6 +35,16 @@ impl FromLiteral for Value {
             Literal::String(v) => Some(Value::String(v)),
             Literal::Bool(v) => Some(Value::Boolean(v)),
             Literal::Null => None,
+            Literal::Array(elements) => elements
+                .into_iter()
+                .map(Value::from_literal)
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array),
+            Literal::Object(members) => members
+                .into_iter()
+                .map(|(name, value)| Value::from_literal(value).map(|v| (name, v)))
+                .collect::<Option<Table>>()
+                .map(Value::Table),
         }
     }
 }
@@ -58,6 +70,10 @@ impl VariantValue for Value {
         self.is_str()
     }
 
+    fn is_number(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
     fn is_array(&self) -> bool {
         self.is_array()
     }
@@ -74,6 +90,18 @@ impl VariantValue for Value {
         self.as_str()
     }
 
+    fn as_i64(&self) -> Option<i64> {
+        self.as_integer()
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(v) => Some(*v as f64),
+            Value::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
     fn as_array(&self) -> Option<&Self::VariantArray> {
         self.as_array()
     }
@@ -84,6 +112,8 @@ impl VariantValue for Value {
 
     fn is_less_than(&self, other: &Self) -> bool {
         match (self, other) {
+            (Value::Integer(l), Value::Integer(r)) => l < r,
+            (Value::Float(l), Value::Float(r)) => l < r,
             (Value::Integer(l), Value::Float(r)) => NumCmp::num_lt(*l, *r),
             (Value::Float(l), Value::Integer(r)) => NumCmp::num_lt(*l, *r),
             (Value::String(l), Value::String(r)) => l < r,
@@ -95,11 +125,29 @@ impl VariantValue for Value {
         match (self, other) {
             (Value::Integer(l), Value::Float(r)) => NumCmp::num_eq(*l, *r),
             (Value::Float(l), Value::Integer(r)) => NumCmp::num_eq(*l, *r),
+            (Value::Array(l), Value::Array(r)) => {
+                l.len() == r.len() && l.iter().zip(r).all(|(l, r)| l.is_equal_to(r))
+            }
+            (Value::Table(l), Value::Table(r)) => {
+                l.len() == r.len()
+                    && l.iter()
+                        .all(|(k, l)| r.get(k).is_some_and(|r| l.is_equal_to(r)))
+            }
             _ => self == other,
         }
     }
 }
 
+impl VariantBuilder for Value {
+    fn build_array(elements: Vec<Self>) -> Self {
+        Value::Array(elements)
+    }
+
+    fn build_object(members: Vec<(String, Self)>) -> Self {
+        Value::Table(members.into_iter().collect())
+    }
+}
+
 impl ConcreteVariantArray for Vec<Value> {
     type Value = Value;
 
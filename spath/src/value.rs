@@ -16,6 +16,8 @@
 
 use std::fmt;
 
+use crate::PathElement;
+
 /// A literal variant value that can be represented in an SPath query.
 #[derive(Debug, Clone)]
 pub enum Literal {
@@ -29,6 +31,10 @@ pub enum Literal {
     Bool(bool),
     /// `null`.
     Null,
+    /// An array of literals.
+    Array(Vec<Literal>),
+    /// An object of literals, with members in their written order.
+    Object(Vec<(String, Literal)>),
 }
 
 impl fmt::Display for Literal {
@@ -39,6 +45,24 @@ impl fmt::Display for Literal {
             Literal::String(s) => write!(f, "'{s}'"),
             Literal::Bool(b) => write!(f, "{b}"),
             Literal::Null => write!(f, "null"),
+            Literal::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    let comma = if i == elements.len() - 1 { "" } else { "," };
+                    write!(f, "{element}{comma}")?;
+                }
+                write!(f, "]")
+            }
+            Literal::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in members.iter().enumerate() {
+                    let comma = if i == members.len() - 1 { "" } else { "," };
+                    // quotes and backslashes in the name are escaped as in a normalized path
+                    let name = PathElement::Name(name);
+                    write!(f, "'{name}':{value}{comma}")?;
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
@@ -60,8 +84,14 @@ pub trait VariantValue: FromLiteral {
     fn is_null(&self) -> bool;
     /// Whether the value is a boolean.
     fn is_boolean(&self) -> bool;
-    /// Whether the value is a number.
+    /// Whether the value is a string.
     fn is_string(&self) -> bool;
+    /// Whether the value is a number, either an integer or a float.
+    ///
+    /// Defaults to whether [`as_f64`](Self::as_f64) converts the value.
+    fn is_number(&self) -> bool {
+        self.as_f64().is_some()
+    }
     /// Whether the value is an array.
     fn is_array(&self) -> bool;
     /// Whether the value is an object.
@@ -70,6 +100,19 @@ pub trait VariantValue: FromLiteral {
     fn as_bool(&self) -> Option<bool>;
     /// Convert the value to a str; [`None`] if the value is not a string.
     fn as_str(&self) -> Option<&str>;
+    /// Convert the value to an i64; [`None`] if the value is not an integer representable as i64.
+    ///
+    /// Defaults to [`None`]; implement it along with [`as_f64`](Self::as_f64) to support numeric
+    /// functions and arithmetic.
+    fn as_i64(&self) -> Option<i64> {
+        None
+    }
+    /// Convert the value to an f64; [`None`] if the value is not a number.
+    ///
+    /// Integers are converted to the nearest f64. Defaults to [`None`].
+    fn as_f64(&self) -> Option<f64> {
+        None
+    }
     /// Convert the value to an array; [`None`] if the value is not an array.
     fn as_array(&self) -> Option<&Self::VariantArray>;
     /// Convert the value to an object; [`None`] if the value is not an object.
@@ -82,6 +125,14 @@ pub trait VariantValue: FromLiteral {
     fn is_equal_to(&self, other: &Self) -> bool;
 }
 
+/// A trait for building new variant values, e.g., the objects constructed by projections.
+pub trait VariantBuilder: VariantValue + Clone {
+    /// Build an array from the elements.
+    fn build_array(elements: Vec<Self>) -> Self;
+    /// Build an object from the members, in order.
+    fn build_object(members: Vec<(String, Self)>) -> Self;
+}
+
 /// A trait for the concrete variant array type associated with a variant value.
 pub trait ConcreteVariantArray {
     /// The type of the value in the array.
@@ -0,0 +1,105 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "aggregate-functions", feature = "json", feature = "toml"))]
+
+//! Conformance tests of aggregate functions over both JSON and TOML values.
+
+mod common;
+
+use common::eval_spath_over_backends;
+use insta::assert_compact_json_snapshot;
+use serde_json::json;
+
+fn testdata() -> serde_json::Value {
+    json!({
+        "orders": [
+            {
+                "id": "A",
+                "tags": ["new", "gift", "new"],
+                "lines": [{"qty": 2, "price": 1.5}, {"qty": 10, "price": 3}]
+            },
+            {
+                "id": "B",
+                "tags": [],
+                "lines": [{"qty": 1, "price": 100}, {"qty": "n/a", "price": 2.5}]
+            },
+            {
+                "id": "C",
+                "tags": ["gift"],
+                "lines": []
+            }
+        ]
+    })
+}
+
+fn eval_spath(spath: &str) -> Vec<serde_json::Value> {
+    eval_spath_over_backends(spath, &testdata())
+}
+
+#[test]
+fn test_numeric_aggregates() {
+    let result = eval_spath(r#"$.orders[?sum(@.lines[*].qty) > 10].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A"]"#);
+    let result = eval_spath(r#"$.orders[?sum(@.lines[*].qty) == 1].id"#);
+    assert_compact_json_snapshot!(result, @r#"["B"]"#);
+    let result = eval_spath(r#"$.orders[?sum(@.lines[*].qty) == 0].id"#);
+    assert_compact_json_snapshot!(result, @r#"["C"]"#);
+    let result = eval_spath(r#"$.orders[?avg(@.lines[*].price) == 2.25].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A"]"#);
+    let result = eval_spath(r#"$.orders[?avg(@.lines[*].price) == 51.25].id"#);
+    assert_compact_json_snapshot!(result, @r#"["B"]"#);
+    let result = eval_spath(r#"$.orders[?min(@.lines[*].price) == 1.5].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A"]"#);
+    let result = eval_spath(r#"$.orders[?max(@.lines[*].qty) == 1].id"#);
+    assert_compact_json_snapshot!(result, @r#"["B"]"#);
+    // no numbers
+    let result = eval_spath(r#"$.orders[?avg(@.lines[*].qty) == max(@.tags)].id"#);
+    assert_compact_json_snapshot!(result, @r#"["C"]"#);
+}
+
+#[test]
+fn test_flatten_arrays() {
+    let value = json!({"a": [1, 2, [3]], "b": 4});
+    let result = eval_spath_over_backends(r#"$[?sum(@.a) == 3]"#, &json!([value]));
+    assert_compact_json_snapshot!(result, @r#"[{"a": [1, 2, [3]], "b": 4}]"#);
+    let result = eval_spath_over_backends(r#"$[?sum(@.*) == 7]"#, &json!([value]));
+    assert_compact_json_snapshot!(result, @r#"[{"a": [1, 2, [3]], "b": 4}]"#);
+}
+
+#[test]
+fn test_distinct_count() {
+    let result = eval_spath(r#"$.orders[?distinct_count(@.tags) == 2].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A"]"#);
+    let result = eval_spath(r#"$.orders[?distinct_count(@.tags) == 0].id"#);
+    assert_compact_json_snapshot!(result, @r#"["B"]"#);
+    let result = eval_spath(r#"$.orders[?distinct_count(@.lines[*].qty) == 2].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A", "B"]"#);
+}
+
+#[test]
+fn test_quantifiers() {
+    let result = eval_spath(r#"$.orders[?any(@.lines[*], @.qty >= 10)].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A"]"#);
+    let result = eval_spath(r#"$.orders[?all(@.lines[*], @.price < 10)].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A", "C"]"#);
+    let result = eval_spath(r#"$.orders[?any(@.tags, @ == 'gift' || @ == 'sale')].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A", "C"]"#);
+    // a query predicate tests existence
+    let result = eval_spath(r#"$.orders[?!all(@.lines, @.qty)].id"#);
+    assert_compact_json_snapshot!(result, @"[]");
+    // the root is still accessible
+    let result = eval_spath(r#"$.orders[?any(@.tags, @ == $.orders[0].tags[1])].id"#);
+    assert_compact_json_snapshot!(result, @r#"["A", "C"]"#);
+}
@@ -12,7 +12,61 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[allow(dead_code)]
 pub fn manifest_dir() -> std::path::PathBuf {
     let dir = env!("CARGO_MANIFEST_DIR");
     std::path::PathBuf::from(dir).canonicalize().unwrap()
 }
+
+/// Evaluate the query over both the JSON and TOML representations of `value`, assert that the
+/// results are the same, and return them as JSON values.
+#[cfg(all(feature = "json", feature = "toml"))]
+#[allow(dead_code)]
+pub fn eval_spath_over_backends(spath: &str, value: &serde_json::Value) -> Vec<serde_json::Value> {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let json_spath = spath::SPath::parse_with_registry(spath, registry).unwrap();
+    let json_result = json_spath
+        .query(value)
+        .unwrap()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let toml_value = toml::Value::try_from(value).unwrap();
+    let registry = spath::toml::BuiltinFunctionRegistry::default();
+    let toml_spath = spath::SPath::parse_with_registry(spath, registry).unwrap();
+    let toml_result = toml_spath
+        .query(&toml_value)
+        .unwrap()
+        .into_iter()
+        .map(|v| serde_json::to_value(v).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(json_result, toml_result, "backends disagree on {spath}");
+    json_result
+}
+
+#[cfg(all(feature = "json", feature = "toml"))]
+#[allow(dead_code)]
+pub fn eval_extended_spath_over_backends(
+    spath: &str,
+    extensions: spath::Extensions,
+    value: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let json_spath = spath::SPath::parse_with_extensions(spath, registry, extensions).unwrap();
+    let json_result = json_spath.query_projected(value).unwrap().all();
+
+    let toml_value = toml::Value::try_from(value).unwrap();
+    let registry = spath::toml::BuiltinFunctionRegistry::default();
+    let toml_spath = spath::SPath::parse_with_extensions(spath, registry, extensions).unwrap();
+    let toml_result = toml_spath
+        .query_projected(&toml_value)
+        .unwrap()
+        .into_iter()
+        .map(|v| serde_json::to_value(v).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(json_result, toml_result, "backends disagree on {spath}");
+    json_result
+}
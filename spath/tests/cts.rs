@@ -0,0 +1,193 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "json", feature = "regex"))]
+
+//! Runs the [JSONPath Compliance Test Suite][cts] against the strict RFC 9535 mode.
+//!
+//! The suite is vendored as `testdata/cts.json`; update it with `cargo x cts --rev <rev>`. Cases
+//! that are known to deviate are listed by name in `testdata/cts-allowlist.txt`.
+//!
+//! [cts]: https://github.com/jsonpath-standard/jsonpath-compliance-test-suite
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::manifest_dir;
+use serde_json::Value;
+use spath::SPath;
+
+struct TestCase {
+    name: String,
+    selector: String,
+    document: Value,
+    expected: Expected,
+}
+
+enum Expected {
+    InvalidSelector,
+    /// Any of the listed orderings of values and normalized paths is acceptable.
+    Results {
+        values: Vec<Vec<Value>>,
+        paths: Vec<Vec<String>>,
+    },
+}
+
+impl TestCase {
+    fn from_json(case: &Value) -> TestCase {
+        let name = case["name"].as_str().expect("test case name").to_string();
+        let selector = case["selector"]
+            .as_str()
+            .unwrap_or_else(|| panic!("{name}: missing selector"))
+            .to_string();
+        let document = case.get("document").cloned().unwrap_or(Value::Null);
+
+        let expected = if case.get("invalid_selector") == Some(&Value::Bool(true)) {
+            Expected::InvalidSelector
+        } else {
+            let values = match (case.get("result"), case.get("results")) {
+                (Some(result), _) => vec![array_of(result, Value::clone)],
+                (None, Some(results)) => array_of(results, |r| array_of(r, Value::clone)),
+                (None, None) => panic!("{name}: missing result"),
+            };
+            let paths = match (case.get("result_paths"), case.get("results_paths")) {
+                (Some(paths), _) => vec![array_of(paths, string_of)],
+                (None, Some(paths)) => array_of(paths, |p| array_of(p, string_of)),
+                (None, None) => vec![],
+            };
+            Expected::Results { values, paths }
+        };
+
+        TestCase {
+            name,
+            selector,
+            document,
+            expected,
+        }
+    }
+
+    /// Run the case and describe the mismatch, if any.
+    fn run(&self) -> Result<(), String> {
+        let registry = spath::json::BuiltinFunctionRegistry::default();
+        let result = SPath::parse_with_registry(&self.selector, registry);
+
+        let (values, paths) = match &self.expected {
+            Expected::InvalidSelector => {
+                return match result {
+                    Ok(spath) => Err(format!("parsed as {spath}, but should have failed")),
+                    Err(_) => Ok(()),
+                };
+            }
+            Expected::Results { values, paths } => (values, paths),
+        };
+
+        let spath = result.map_err(|err| format!("failed to parse: {err}"))?;
+
+        let actual = spath
+            .query(&self.document)
+            .map_err(|err| format!("failed to query: {err}"))?
+            .all();
+        if !values.iter().any(|v| v.iter().eq(actual.iter().copied())) {
+            return Err(format!(
+                "query returned {actual:?}, expected any of {values:?}"
+            ));
+        }
+
+        let located = spath
+            .query_located(&self.document)
+            .map_err(|err| format!("failed to query: {err}"))?;
+        let actual = located.nodes().collect::<Vec<_>>();
+        if !values.iter().any(|v| v.iter().eq(actual.iter().copied())) {
+            return Err(format!(
+                "query_located returned {actual:?}, expected any of {values:?}"
+            ));
+        }
+
+        if !paths.is_empty() {
+            let actual = located
+                .locations()
+                .map(|path| path.to_string())
+                .collect::<Vec<_>>();
+            if !paths.contains(&actual) {
+                return Err(format!(
+                    "query_located returned paths {actual:?}, expected any of {paths:?}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn array_of<R>(value: &Value, f: impl Fn(&Value) -> R) -> Vec<R> {
+    value.as_array().expect("an array").iter().map(f).collect()
+}
+
+fn string_of(value: &Value) -> String {
+    value.as_str().expect("a string").to_string()
+}
+
+fn allowlist() -> BTreeSet<String> {
+    let path = manifest_dir().join("testdata").join("cts-allowlist.txt");
+    let content = std::fs::read_to_string(path).unwrap();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_compliance_suite() {
+    let path = manifest_dir().join("testdata").join("cts.json");
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    let suite: Value = serde_json::from_str(&content).unwrap();
+    let cases = array_of(&suite["tests"], TestCase::from_json);
+    assert!(
+        !cases.is_empty(),
+        "no compliance cases in {}",
+        path.display()
+    );
+
+    let mut allowlist = allowlist();
+    let mut failures = vec![];
+    for case in &cases {
+        let allowed = allowlist.remove(&case.name);
+        match (case.run(), allowed) {
+            (Ok(()), false) | (Err(_), true) => {}
+            (Ok(()), true) => failures.push(format!(
+                "{}: passes now, remove it from the allowlist",
+                case.name
+            )),
+            (Err(err), false) => failures.push(format!(
+                "{} (selector {:?}): {err}",
+                case.name, case.selector
+            )),
+        }
+    }
+    for name in allowlist {
+        failures.push(format!("{name}: allowlisted, but not in the suite"));
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} compliance cases failed:\n{}",
+        failures.len(),
+        cases.len(),
+        failures.join("\n")
+    );
+}
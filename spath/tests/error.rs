@@ -0,0 +1,235 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "json")]
+
+//! Tests of the messages of parse errors.
+
+use insta::assert_snapshot;
+use spath::Extension;
+use spath::ParseError;
+use spath::ParseErrorKind;
+use spath::SPath;
+
+fn parse_error(spath: &str) -> String {
+    try_parse(spath).to_string()
+}
+
+fn try_parse(spath: &str) -> ParseError {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    SPath::<serde_json::Value>::parse_with_registry(spath, registry).unwrap_err()
+}
+
+#[test]
+fn test_expected_tokens() {
+    assert_snapshot!(parse_error("$[1,]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[1,]
+          |     ^ expected one of `*`, string literal, integer literal, `:`, or `?`
+          |
+        "#);
+    assert_snapshot!(parse_error("$.a b"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a b
+          |     ^ expected one of `..`, `.`, `[`, or end of input
+          |
+        "#);
+    assert_snapshot!(parse_error("$.a["), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a[
+          |     ^ expected one of `*`, string literal, integer literal, `:`, or `?`
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@.a && ]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a && ]
+          |           ^ expected one of 11 possible tokens
+          |
+          = note: expected one of `!`, `(`, string literal, integer literal, number literal, `true`, `false`, `null`, `$`, `@`, or identifier
+        "#);
+}
+
+#[test]
+fn test_equality_hints() {
+    assert_snapshot!(parse_error("$[?@.a = 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a = 1]
+          |        ^ the alternative operator `=` and `<>` extension is not enabled
+          |
+          = help: did you mean `==`?
+        "#);
+    assert_snapshot!(parse_error("$[?@.a <> 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a <> 1]
+          |        ^^ the alternative operator `=` and `<>` extension is not enabled
+          |
+          = help: did you mean `!=`?
+        "#);
+    assert_snapshot!(parse_error("$[?@.a === 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a === 1]
+          |          ^ expected another comparable
+          |
+          = help: JSONPath compares with `==` and `!=` rather than `===` and `!==`
+        "#);
+    assert_snapshot!(parse_error("$[?@.a !== 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a !== 1]
+          |          ^ expected another comparable
+          |
+          = help: JSONPath compares with `==` and `!=` rather than `===` and `!==`
+        "#);
+}
+
+#[test]
+fn test_function_name_hints() {
+    assert_snapshot!(parse_error("$[?lenght(@.a) > 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?lenght(@.a) > 1]
+          |    ^^^^^^ function 'lenght' is not defined
+          |
+          = help: did you mean `length`?
+        "#);
+    assert_snapshot!(parse_error("$[?Count(@.*) > 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?Count(@.*) > 1]
+          |    ^^^^^ the lenient function name extension is not enabled
+          |
+          = help: did you mean `count`?
+        "#);
+    assert_snapshot!(parse_error("$[?foo(@.a)]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?foo(@.a)]
+          |    ^^^ function 'foo' is not defined
+          |
+        "#);
+}
+
+#[test]
+fn test_filter_selector_hints() {
+    assert_snapshot!(parse_error("$.a[(@.length-1)]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a[(@.length-1)]
+          |     ^ expected one of `*`, string literal, `:`, integer literal, or `?`
+          |
+          = help: script expressions are not supported; did you mean a filter selector like `[?(@.b == 1)]`?
+        "#);
+    assert_snapshot!(parse_error("$.a[@.b == 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a[@.b == 1]
+          |     ^ expected one of `*`, string literal, `:`, integer literal, or `?`
+          |
+          = help: filter selectors start with `?`, e.g., `[?@.b == 1]`
+        "#);
+    assert_snapshot!(parse_error("$.a[0, !@.b]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a[0, !@.b]
+          |        ^ expected one of `*`, string literal, `:`, integer literal, or `?`
+          |
+          = help: filter selectors start with `?`, e.g., `[?@.b == 1]`
+        "#);
+}
+
+#[test]
+fn test_error_accessors() {
+    let err = try_parse("$[1,]");
+    assert_eq!(err.query(), "$[1,]");
+    assert_eq!(err.range(), 4..5);
+    assert_eq!(err.kind(), &ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.expected().len(), 5);
+    assert_eq!(err.help(), None);
+
+    let err = try_parse("$[?lenght(@.a) > 1]");
+    assert_eq!(err.range(), 3..9);
+    assert!(matches!(err.kind(), ParseErrorKind::FunctionValidation(_)));
+    assert_eq!(err.message(), "function 'lenght' is not defined");
+    assert_eq!(err.help(), Some("did you mean `length`?"));
+    assert!(std::error::Error::source(&err).is_some());
+
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let extensions = Extension::Sort.into();
+    let err =
+        SPath::<serde_json::Value>::parse_with_extensions("$[sort(@.*)]", registry, extensions)
+            .unwrap_err();
+    assert!(matches!(err.kind(), ParseErrorKind::NonSingularQuery(_)));
+    assert_eq!(err.range(), 7..8);
+
+    let err = try_parse("$.a~");
+    assert_eq!(
+        err.kind(),
+        &ParseErrorKind::ExtensionNotEnabled(Extension::KeySelector)
+    );
+
+    assert_eq!(
+        try_parse("$['\\q']").kind(),
+        &ParseErrorKind::InvalidLiteral
+    );
+    assert_eq!(try_parse("$.a#b").kind(), &ParseErrorKind::Lexing);
+}
+
+#[test]
+fn test_styled_rendering() {
+    let err = try_parse("$[1,]");
+    let styled = err.render_styled();
+    assert!(styled.contains("\x1b["));
+    assert!(!err.to_string().contains("\x1b["));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_error() {
+    use insta::assert_compact_json_snapshot;
+
+    assert_compact_json_snapshot!(try_parse("$[?lenght(@.a) > 1]"), @r#"
+        {
+          "kind": "function_validation",
+          "message": "function 'lenght' is not defined",
+          "query": "$[?lenght(@.a) > 1]",
+          "start": 3,
+          "end": 9,
+          "expected": [],
+          "help": "did you mean `length`?"
+        }
+        "#);
+    assert_compact_json_snapshot!(try_parse("$.a b"), @r#"
+        {
+          "kind": "unexpected_token",
+          "message": "expected one of `..`, `.`, `[`, or end of input",
+          "query": "$.a b",
+          "start": 4,
+          "end": 5,
+          "expected": [
+            "`..`",
+            "`.`",
+            "`[`",
+            "end of input"
+          ],
+          "help": null
+        }
+        "#);
+}
@@ -0,0 +1,1417 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "json", feature = "toml"))]
+
+//! Conformance tests of syntax extensions beyond RFC 9535 over both JSON and TOML values.
+
+mod common;
+
+use common::eval_extended_spath_over_backends;
+use insta::assert_compact_json_snapshot;
+use insta::assert_snapshot;
+use serde_json::json;
+use spath::Extension;
+use spath::Extensions;
+use spath::Literal;
+use spath::Params;
+use spath::QueryError;
+use spath::SPath;
+
+fn testdata() -> serde_json::Value {
+    json!({
+        "features": {
+            "search": {"enabled": true, "tags": ["beta", "web"]},
+            "export": {"enabled": false, "tags": []}
+        },
+        "users": [
+            {"id": 1, "name": "alice", "roles": ["admin"]},
+            {"id": 2, "name": "bob", "roles": []}
+        ]
+    })
+}
+
+fn eval_spath(spath: &str, extensions: impl Into<Extensions>) -> Vec<serde_json::Value> {
+    eval_extended_spath_over_backends(spath, extensions.into(), &testdata())
+}
+
+fn parse_error(spath: &str, extensions: Extensions) -> String {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    SPath::parse_with_extensions(spath, registry, extensions)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_key_selector() {
+    let result = eval_spath("$.features.*~", Extension::KeySelector);
+    assert_compact_json_snapshot!(result, @r#"["export", "search"]"#);
+    let result = eval_spath("$.users[*]~", Extension::KeySelector);
+    assert_compact_json_snapshot!(result, @"[0, 1]");
+    let result = eval_spath("$.features[?@.enabled == true]~", Extension::KeySelector);
+    assert_compact_json_snapshot!(result, @r#"["search"]"#);
+    let result = eval_spath("$..tags~", Extension::KeySelector);
+    assert_compact_json_snapshot!(result, @r#"["tags", "tags"]"#);
+    let result = eval_spath("$~", Extension::KeySelector);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath("$.users[*]", Extension::KeySelector);
+    assert_compact_json_snapshot!(result, @r#"[{"id": 1, "name": "alice", "roles": ["admin"]}, {"id": 2, "name": "bob", "roles": []}]"#);
+}
+
+#[test]
+fn test_key_selector_borrowed_queries() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions("$.features.*~", registry, Extensions::all()).unwrap();
+    assert_snapshot!(spath, @"$.features.*~");
+    assert!(!spath.is_borrowed());
+    let err = spath.query(&value).unwrap_err();
+    assert_snapshot!(err, @"query yields nodes not borrowed from the value; use `query_nodes` or `query_projected` instead");
+    assert!(matches!(
+        spath.query_located(&value),
+        Err(QueryError::NotBorrowed)
+    ));
+    let nodes = spath.query_nodes(&value).unwrap();
+    assert!(nodes.iter().all(|node| node.is_owned()));
+}
+
+#[test]
+fn test_key_selector_disabled() {
+    assert_snapshot!(parse_error("$.features.*~", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.features.*~
+          |             ^ the key selector `~` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$.features~.*", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.features~.*
+          |            ^ expected `|` or end of input
+          |
+        "#);
+}
+
+#[test]
+fn test_parent_segment() {
+    let result = eval_spath("$..[?@ == 'admin']^^", Extension::ParentSegment);
+    assert_compact_json_snapshot!(result, @r#"[{"id": 1, "name": "alice", "roles": ["admin"]}]"#);
+    let result = eval_spath(
+        "$.users[?@.name == 'bob'].roles^.id",
+        Extension::ParentSegment,
+    );
+    assert_compact_json_snapshot!(result, @"[2]");
+    let result = eval_spath("$.users[0].roles[0]^^.name", Extension::ParentSegment);
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath("$^", Extension::ParentSegment);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath("$.users^^", Extension::ParentSegment);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath(
+        "$.users[?$.features.search.tags[0]^^.enabled].id",
+        Extension::ParentSegment,
+    );
+    assert_compact_json_snapshot!(result, @"[1, 2]");
+    let extensions = Extensions::none()
+        .with(Extension::ParentSegment)
+        .with(Extension::KeySelector);
+    let result = eval_spath("$.features.*.tags[?@ == 'web']^^~", extensions);
+    assert_compact_json_snapshot!(result, @r#"["search"]"#);
+    // a singular query with parent segments as a value type function argument
+    let result = eval_spath(
+        "$.users[?length($.users[0]^) == 2].id",
+        Extension::ParentSegment,
+    );
+    assert_compact_json_snapshot!(result, @"[1, 2]");
+    let result = eval_spath("$.users[?length($^) > 0].id", Extension::ParentSegment);
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_parent_segment_located() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath =
+        SPath::parse_with_extensions("$..[?@ == 'admin']^^", registry, Extensions::all()).unwrap();
+    assert_snapshot!(spath, @"$..[?@=='admin']^^");
+    let result = spath.query_located(&value).unwrap();
+    let locations = result
+        .locations()
+        .map(|l| l.to_string())
+        .collect::<Vec<_>>();
+    assert_compact_json_snapshot!(locations, @r#"["$['users'][0]"]"#);
+}
+
+#[test]
+fn test_parent_segment_disabled() {
+    assert_snapshot!(parse_error("$.users^", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.users^
+          |        ^ the parent segment `^` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$.users[?@.roles^]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.users[?@.roles^]
+          |                 ^ expected one of 27 possible tokens
+          |
+          = note: expected one of `..`, `.`, `[`, `=~`, `!~`, `*`, `/`, `%`, `+`, `-`, integer literal, number literal, `==`, `!=`, `<=`, `<`, `>=`, `>`, `=`, `<>`, `in`, `nin`, `contains`, `&&`, `||`, `,`, or `]`
+        "#);
+}
+
+fn eval_spath_with(spath: &str, params: &[(&str, Literal)]) -> Vec<serde_json::Value> {
+    let value = testdata();
+    let extensions = Extensions::from(Extension::Parameters);
+
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let json_spath = SPath::parse_with_extensions(spath, registry, extensions).unwrap();
+    let mut json_params = Params::new();
+    for (name, literal) in params {
+        json_params.bind_literal(*name, literal.clone());
+    }
+    let json_result = json_spath
+        .query_with(&value, &json_params)
+        .unwrap()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let toml_value = toml::Value::try_from(&value).unwrap();
+    let registry = spath::toml::BuiltinFunctionRegistry::default();
+    let toml_spath = SPath::parse_with_extensions(spath, registry, extensions).unwrap();
+    let mut toml_params = Params::new();
+    for (name, literal) in params {
+        toml_params.bind_literal(*name, literal.clone());
+    }
+    let toml_result = toml_spath
+        .query_with(&toml_value, &toml_params)
+        .unwrap()
+        .into_iter()
+        .map(|v| serde_json::to_value(v).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(json_result, toml_result, "backends disagree on {spath}");
+    json_result
+}
+
+#[test]
+fn test_parameters() {
+    let result = eval_spath_with("$.users[?@.id == $$uid].name", &[("uid", Literal::Int(2))]);
+    assert_compact_json_snapshot!(result, @r#"["bob"]"#);
+    let result = eval_spath_with(
+        "$.users[?@.id > $$min && @.name != $$name].name",
+        &[
+            ("min", Literal::Int(0)),
+            ("name", Literal::String("bob".to_string())),
+        ],
+    );
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath_with(
+        "$.users[?length(@.roles) == $$count].id",
+        &[("count", Literal::Int(1))],
+    );
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval_spath_with(
+        "$.users[?$$uid == @.id].name",
+        &[("uid", Literal::Float(1.0))],
+    );
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+}
+
+#[test]
+fn test_parameters_bound_to_values() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$.users[?@.roles == $$roles].name",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$.users[?@['roles']==$$roles].name");
+
+    let mut params = Params::new();
+    params.bind("roles", json!(["admin"]));
+    let result = spath.query_with(&value, &params).unwrap().all();
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+
+    let result = spath.query_with(&value, &Params::new()).unwrap_err();
+    assert_snapshot!(result, @"parameter `$$roles` is not bound");
+    // the methods without parameters check that none is referenced
+    let result = spath.query(&value).unwrap_err();
+    assert_snapshot!(result, @"parameter `$$roles` is not bound");
+    assert!(matches!(
+        spath.query_located(&value),
+        Err(QueryError::UnboundParam(_))
+    ));
+    assert!(matches!(
+        spath.query_nodes(&value),
+        Err(QueryError::UnboundParam(_))
+    ));
+    assert!(matches!(
+        spath.query_projected(&value),
+        Err(QueryError::UnboundParam(_))
+    ));
+}
+
+#[test]
+fn test_parameters_disabled() {
+    assert_snapshot!(parse_error("$.users[?@.id == $$uid]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.users[?@.id == $$uid]
+          |                  ^^^^^ the named parameter `$$name` extension is not enabled
+          |
+        "#);
+}
+
+#[test]
+fn test_composite_literals() {
+    let ext = Extension::CompositeLiterals;
+    let result = eval_spath("$.users[?@.roles == ['admin']].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath("$.users[?@.roles == []].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["bob"]"#);
+    let result = eval_spath("$.features[?@.tags != ['web', 'beta']].enabled", ext);
+    assert_compact_json_snapshot!(result, @"[false, true]");
+    let result = eval_spath(
+        "$.users[?@ == {'roles': [], 'name': 'bob', 'id': 2.0}].id",
+        ext,
+    );
+    assert_compact_json_snapshot!(result, @"[2]");
+    let result = eval_spath("$.users[?length(['a', 'b']) == @.id].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["bob"]"#);
+}
+
+#[test]
+fn test_composite_literals_parse() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$[?@ == {'a': [1, 2.5, 'x', true], 'b': {}}]",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$[?@=={'a':[1,2.5,'x',true],'b':{}}]");
+
+    // member names are escaped so that the query round-trips
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        r#"$[?@ == {'it\'s': 1, "a\\b": 2}]"#,
+        registry.clone(),
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @r#"$[?@=={'it\'s':1,'a\\b':2}]"#);
+    let reparsed =
+        SPath::parse_with_extensions(&spath.to_string(), registry, Extensions::all()).unwrap();
+    assert_eq!(spath.to_string(), reparsed.to_string());
+    let value = json!([{"it's": 1, "a\\b": 2}, {"it's": 1}]);
+    let result = reparsed.query(&value).unwrap().all();
+    assert_compact_json_snapshot!(result, @r#"[{"a\\b": 2, "it's": 1}]"#);
+    assert_snapshot!(parse_error("$[?@ == ['a']]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@ == ['a']]
+          |         ^ the composite literal extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@ == {'a': 1, 'a': 2}]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@ == {'a': 1, 'a': 2}]
+          |         ^ duplicate member name 'a' in object literal
+          |
+        "#);
+}
+
+#[test]
+fn test_membership_operators() {
+    let ext = Extensions::none()
+        .with(Extension::MembershipOperators)
+        .with(Extension::CompositeLiterals);
+    let result = eval_spath("$.users[?@.name in ['bob', 'carol']].id", ext);
+    assert_compact_json_snapshot!(result, @"[2]");
+    let result = eval_spath("$.users[?@.name nin ['bob', 'carol']].id", ext);
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval_spath("$.users[?@.roles contains 'admin'].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath("$.users[?'admin' in @.roles].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath("$.users[?length(@.roles) in [0, 2]].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["bob"]"#);
+    let result = eval_spath("$.users[?[1.0, 3] contains @.id].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath("$.users[?@.email in ['bob']].name", ext);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath("$.users[?@.email nin ['bob']].name", ext);
+    assert_compact_json_snapshot!(result, @r#"["alice", "bob"]"#);
+    let result = eval_spath("$.users[?@.name in @.email].name", ext);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath("$.users[?@.name contains 'b'].name", ext);
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_membership_operators_parse() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$[?$$role in @.roles && @.tags contains 'x']",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$[?$$role in @['roles'] && @['tags'] contains 'x']");
+    assert_snapshot!(parse_error("$[?@.status in $.statuses]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.status in $.statuses]
+          |             ^^ the membership operator extension is not enabled
+          |
+        "#);
+}
+
+#[test]
+fn test_arithmetic() {
+    let value = json!({
+        "items": [
+            {"name": "a", "price": 12.5, "qty": 10},
+            {"name": "b", "price": 3, "qty": 7},
+            {"name": "c", "price": 50, "qty": 0},
+            {"name": "d", "price": "free", "qty": 1}
+        ],
+        "events": [
+            {"start": 100, "end": 4000},
+            {"start": 7, "end": 3000}
+        ]
+    });
+    let eval =
+        |spath| eval_extended_spath_over_backends(spath, Extension::Arithmetic.into(), &value);
+
+    let result = eval("$.items[?@.price * @.qty > 100].name");
+    assert_compact_json_snapshot!(result, @r#"["a"]"#);
+    let result = eval("$.events[?@.end - @.start > 3600].start");
+    assert_compact_json_snapshot!(result, @"[100]");
+    let result = eval("$.items[?@.qty + 1 * 2 == 9].name");
+    assert_compact_json_snapshot!(result, @r#"["b"]"#);
+    let result = eval("$.items[?(@.qty + 1) * 2 == 16].name");
+    assert_compact_json_snapshot!(result, @r#"["b"]"#);
+    let result = eval("$.items[?@.qty -1 == 9].name");
+    assert_compact_json_snapshot!(result, @r#"["a"]"#);
+    let result = eval("$.items[?10 - 4 - 3 == @.qty - 4].name");
+    assert_compact_json_snapshot!(result, @r#"["b"]"#);
+    let result = eval("$.items[?@.qty / 2 == 3.5].name");
+    assert_compact_json_snapshot!(result, @r#"["b"]"#);
+    let result = eval("$.items[?@.qty % 2 == 1].name");
+    assert_compact_json_snapshot!(result, @r#"["b", "d"]"#);
+    let result = eval("$.items[?@.price / @.qty == @.nothing].name");
+    assert_compact_json_snapshot!(result, @r#"["c", "d"]"#);
+    let result = eval("$.items[?@.price % @.qty >= 0].name");
+    assert_compact_json_snapshot!(result, @r#"["a", "b"]"#);
+    let result = eval("$.items[?length(@.name) * 2 == 2 && @.price + 0 == 3].name");
+    assert_compact_json_snapshot!(result, @r#"["b"]"#);
+}
+
+#[test]
+fn test_arithmetic_parse() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$[?(@.a - (@.b - 1)) * 2 / (3 % @.c) == -@.d]",
+        registry,
+        Extensions::all(),
+    );
+    assert_snapshot!(spath.unwrap_err(), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?(@.a - (@.b - 1)) * 2 / (3 % @.c) == -@.d]
+          |                                         ^ expected another comparable
+          |
+        "#);
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$[?(@.a - (@.b - 1)) * 2 / (3 % @.c) == @.d -1 * 2]",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$[?(@['a']-(@['b']-1))*2/(3%@['c'])==@['d']-1*2]");
+    assert_snapshot!(parse_error("$[?@.a + 1 == 2]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a + 1 == 2]
+          |        ^ the arithmetic extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@.a == @.b -1]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a == @.b -1]
+          |               ^^ the arithmetic extension is not enabled
+          |
+        "#);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn test_regex_match() {
+    let result = eval_spath("$.users[?@.name =~ /^a/].id", Extension::RegexMatch);
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval_spath("$.users[?@.name !~ /^a/].id", Extension::RegexMatch);
+    assert_compact_json_snapshot!(result, @"[2]");
+    let result = eval_spath("$.users[?@.name =~ /^B/i].id", Extension::RegexMatch);
+    assert_compact_json_snapshot!(result, @"[2]");
+    let result = eval_spath("$.users[?@.name =~ /^B/].id", Extension::RegexMatch);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath("$.users[?@.id =~ /1/].id", Extension::RegexMatch);
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval_spath("$.users[?@.id !~ /1/].id", Extension::RegexMatch);
+    assert_compact_json_snapshot!(result, @"[1, 2]");
+    let result = eval_spath(
+        "$.features[?@.tags[0] =~ /a\\/?/].enabled",
+        Extension::RegexMatch,
+    );
+    assert_compact_json_snapshot!(result, @"[true]");
+    let result = eval_spath(
+        "$.users[?$.users[0].name=~/ice$/ && @.id / 2 == 1].name",
+        Extensions::all(),
+    );
+    assert_compact_json_snapshot!(result, @r#"["bob"]"#);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn test_regex_match_parse() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$[?@.a =~ /x\\/y/ims || !(@.b !~ /a.*b/)]",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @r#"$[?@['a']=~/x\/y/ims || !(@['b']!~/a.*b/)]"#);
+    assert_snapshot!(parse_error("$[?@.a =~ /x/]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a =~ /x/]
+          |        ^^ the regex match operator extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@.a =~ /x/g]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a =~ /x/g]
+          |           ^^^^ unknown regex flag 'g', expected any of 'i', 'm' and 's'
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@.a =~ /(x/]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a =~ /(x/]
+          |           ^^^^ invalid regex: unclosed group
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@.a =~ /x]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a =~ /x]
+          |           ^^^ unterminated regex literal
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@.a =~ 'x']", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a =~ 'x']
+          |           ^^^ expected a regex literal, e.g., `/pattern/i`
+          |
+        "#);
+}
+
+#[test]
+fn test_bounded_descendant() {
+    let eval = |spath: &str| {
+        let value = json!({
+            "name": "root",
+            "a": {"name": "a", "b": {"name": "b", "c": {"name": "c"}}},
+            "list": [{"name": "x"}, [{"name": "y"}]]
+        });
+        eval_extended_spath_over_backends(spath, Extension::BoundedDescendant.into(), &value)
+    };
+
+    let result = eval("$..{1,1}name");
+    assert_compact_json_snapshot!(result, @r#"["root"]"#);
+    let result = eval("$..{1,2}name");
+    assert_compact_json_snapshot!(result, @r#"["root", "a"]"#);
+    let result = eval("$..{2}name");
+    assert_compact_json_snapshot!(result, @r#"["a"]"#);
+    let result = eval("$..{3,}name");
+    assert_compact_json_snapshot!(result, @r#"["b", "c", "x", "y"]"#);
+    let result = eval("$..{1,}name");
+    assert_compact_json_snapshot!(result, @r#"["root", "a", "b", "c", "x", "y"]"#);
+    let result = eval("$..{2,3}['name']");
+    assert_compact_json_snapshot!(result, @r#"["a", "b", "x"]"#);
+    let result = eval("$.list..{1,2}*");
+    assert_compact_json_snapshot!(result, @r#"[{"name": "x"}, [{"name": "y"}], "x", {"name": "y"}]"#);
+}
+
+#[test]
+fn test_bounded_descendant_located() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions("$..{2,3}[0]", registry, Extensions::all()).unwrap();
+    assert_snapshot!(spath, @"$..{2,3}[0]");
+    let paths = spath
+        .query_located(&value)
+        .unwrap()
+        .into_iter()
+        .map(|node| node.location().to_string())
+        .collect::<Vec<_>>();
+    assert_compact_json_snapshot!(paths, @r#"["$['users'][0]"]"#);
+
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions("$..{1,2}*..{1}id", registry, Extensions::all());
+    assert_snapshot!(spath.unwrap(), @"$..{1,2}*..{1,1}id");
+}
+
+#[test]
+fn test_bounded_descendant_parse() {
+    let all = Extensions::all();
+    assert_snapshot!(parse_error("$..{1,2}name", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $..{1,2}name
+          |    ^ the bounded descendant segment `..{min,max}` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$..{0,2}name", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $..{0,2}name
+          |    ^^^^^ the minimum depth must be at least 1
+          |
+        "#);
+    assert_snapshot!(parse_error("$..{3,2}name", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $..{3,2}name
+          |    ^^^^^ the maximum depth must not be less than the minimum depth
+          |
+        "#);
+    assert_snapshot!(parse_error("$..{-1}name", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $..{-1}name
+          |     ^^ invalid depth: invalid digit found in string
+          |
+        "#);
+    assert_snapshot!(parse_error("$..{1,2,3}name", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $..{1,2,3}name
+          |        ^ expected `}`
+          |
+        "#);
+}
+
+#[test]
+fn test_sort() {
+    let eval = |spath: &str| {
+        let value = json!({
+            "books": [
+                {"title": "a", "price": 8.95},
+                {"title": "b", "price": 12.99},
+                {"title": "c"},
+                {"title": "d", "price": 8.99},
+                {"title": "e", "price": 12.99},
+                {"title": "f", "price": "free"}
+            ]
+        });
+        eval_extended_spath_over_backends(spath, Extension::Sort.into(), &value)
+    };
+
+    let result = eval("$.books[sort(@.price)].title");
+    assert_compact_json_snapshot!(result, @r#"["a", "d", "b", "e", "f", "c"]"#);
+    let result = eval("$.books[sort(@.price desc)].title");
+    assert_compact_json_snapshot!(result, @r#"["f", "b", "e", "d", "a", "c"]"#);
+    let result = eval("$.books[sort(@.price desc, @.title desc)].title");
+    assert_compact_json_snapshot!(result, @r#"["f", "e", "b", "d", "a", "c"]"#);
+    let result = eval("$.books[sort(@.price desc)[:3]].title");
+    assert_compact_json_snapshot!(result, @r#"["f", "b", "e"]"#);
+    let result = eval("$.books[sort(@.price asc)[-2:]].title");
+    assert_compact_json_snapshot!(result, @r#"["f", "c"]"#);
+    let result = eval("$.books[sort(@.title)[::-2], 0].title");
+    assert_compact_json_snapshot!(result, @r#"["f", "d", "b", "a"]"#);
+    let result = eval("$[sort(@.price)]");
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_sort_located() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$.users[sort(@.name desc)[:1]].name",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$.users[sort(@['name'] desc)[:1:]].name");
+    let paths = spath
+        .query_located(&value)
+        .unwrap()
+        .into_iter()
+        .map(|node| node.location().to_string())
+        .collect::<Vec<_>>();
+    assert_compact_json_snapshot!(paths, @r#"["$['users'][1]['name']"]"#);
+}
+
+#[test]
+fn test_sort_parse() {
+    let all = Extensions::all();
+    assert_snapshot!(parse_error("$[sort(@.a)]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[sort(@.a)]
+          |   ^^^^ the sort selector extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[sort(@.*)]", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[sort(@.*)]
+          |        ^ wildcard segments are not singular
+          |
+        "#);
+    assert_snapshot!(parse_error("$[sort()]", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[sort()]
+          |        ^ expected `$` or `@`
+          |
+        "#);
+    assert_snapshot!(parse_error("$[sort(@.a up)]", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[sort(@.a up)]
+          |            ^^ expected `)`
+          |
+        "#);
+}
+
+#[test]
+fn test_projection() {
+    let result = eval_spath("$.users[*].{id: @.id, name: @.name}", Extension::Projection);
+    assert_compact_json_snapshot!(result, @r#"[{"id": 1, "name": "alice"}, {"id": 2, "name": "bob"}]"#);
+    let result = eval_spath(
+        "$.users[?@.id == 1].{'user-id': @.id, admin: @.roles[0], guest: @.roles[1]}",
+        Extension::Projection,
+    );
+    assert_compact_json_snapshot!(result, @r#"[{"admin": "admin", "user-id": 1}]"#);
+    let result = eval_spath(
+        "$.features.*.{tags: @.tags[*], total: $.users[*].id, kind: 'feature'}",
+        Extension::Projection,
+    );
+    assert_compact_json_snapshot!(result, @r#"[{"kind": "feature", "tags": [], "total": [1, 2]}, {"kind": "feature", "tags": ["beta", "web"], "total": [1, 2]}]"#);
+    let result = eval_spath(
+        "$.users[*].{id: @.id, roles: ['guest']}",
+        [Extension::Projection, Extension::CompositeLiterals]
+            .into_iter()
+            .collect::<Extensions>(),
+    );
+    assert_compact_json_snapshot!(result, @r#"[{"id": 1, "roles": ["guest"]}, {"id": 2, "roles": ["guest"]}]"#);
+    let result = eval_spath("$.nothing.{id: @.id}", Extension::Projection);
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_projection_owned_results() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath =
+        SPath::parse_with_extensions("$.users[*].{id: @.id}", registry, Extensions::all()).unwrap();
+    assert_snapshot!(spath, @"$.users[*].{'id':@.id}");
+    assert!(!spath.is_borrowed());
+    assert!(matches!(spath.query(&value), Err(QueryError::NotBorrowed)));
+    assert!(matches!(
+        spath.query_located(&value),
+        Err(QueryError::NotBorrowed)
+    ));
+    let err = spath.query_nodes(&value).unwrap_err();
+    assert_snapshot!(err, @"query constructs objects with a projection; use `query_projected` instead");
+    assert_eq!(spath.query_projected(&value).unwrap().len(), 2);
+
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions("$.users[*].id", registry, Extensions::all()).unwrap();
+    assert!(spath.is_borrowed());
+    let result = spath.query_projected(&value).unwrap();
+    assert_compact_json_snapshot!(result.all(), @"[1, 2]");
+}
+
+#[test]
+fn test_projection_display() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    for query in [
+        r#"$.users[*].{id: @.id, 'mail': @.contact.email}"#,
+        r#"$.o.{'it\'s': @.k, "a\\b": 1}"#,
+    ] {
+        let spath =
+            SPath::parse_with_extensions(query, registry.clone(), Extensions::all()).unwrap();
+        let display = spath.to_string();
+        let reparsed =
+            SPath::parse_with_extensions(&display, registry.clone(), Extensions::all()).unwrap();
+        assert_eq!(display, reparsed.to_string());
+    }
+
+    let spath =
+        SPath::parse_with_extensions(r#"$.o.{'it\'s': @.k}"#, registry, Extensions::all()).unwrap();
+    assert_snapshot!(spath, @r#"$.o.{'it\'s':@.k}"#);
+}
+
+#[test]
+fn test_projection_parse() {
+    let all = Extensions::all();
+    assert_snapshot!(parse_error("$.{id: @.id}", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.{id: @.id}
+          |   ^ the projection `.{...}` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$.{id: @.id, id: @.name}", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.{id: @.id, id: @.name}
+          |   ^ duplicate member name 'id' in projection
+          |
+        "#);
+    assert_snapshot!(parse_error("$.{}", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.{}
+          |    ^ expected identifier or string literal
+          |
+        "#);
+    assert_snapshot!(parse_error("$.{id @.id}", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.{id @.id}
+          |       ^ expected `:`
+          |
+        "#);
+    assert_snapshot!(parse_error("$.{id: @.id}.name", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.{id: @.id}.name
+          |             ^ expected `|` or end of input
+          |
+        "#);
+    assert_snapshot!(parse_error("$.*~.{id: @.id}", all), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.*~.{id: @.id}
+          |     ^ expected `|` or end of input
+          |
+        "#);
+}
+
+#[test]
+fn test_union() {
+    let result = eval_spath("$.users[*].name | $.features.*.enabled", Extension::Union);
+    assert_compact_json_snapshot!(result, @r#"["alice", "bob", false, true]"#);
+    let result = eval_spath(
+        "$.nothing | $.users[0].id | $.users[0].id",
+        Extension::Union,
+    );
+    assert_compact_json_snapshot!(result, @"[1, 1]");
+    let result = eval_spath(
+        "$.users~ | $.features.*~ | $.users[*].{n: @.name}",
+        Extensions::all(),
+    );
+    assert_compact_json_snapshot!(result, @r#"["users", "export", "search", {"n": "alice"}, {"n": "bob"}]"#);
+}
+
+#[test]
+fn test_union_located() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$.users[?@.id == 2].name|$.users[*].name",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$.users[?@['id']==2].name | $.users[*].name");
+    let paths = |nodes: spath::LocatedNodeList<'_, serde_json::Value>| {
+        nodes
+            .locations()
+            .map(|location| location.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_compact_json_snapshot!(paths(spath.query_located(&value).unwrap()), @r#"["$['users'][1]['name']", "$['users'][0]['name']", "$['users'][1]['name']"]"#);
+    assert_compact_json_snapshot!(paths(spath.query_located(&value).unwrap().distinct()), @r#"["$['users'][1]['name']", "$['users'][0]['name']"]"#);
+    assert_eq!(spath.query(&value).unwrap().len(), 3);
+    assert!(spath.is_borrowed());
+
+    // the borrowed APIs reject the member names selected by the second branch
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$.users[0].name | $.features.*~",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert!(!spath.is_borrowed());
+    assert!(matches!(spath.query(&value), Err(QueryError::NotBorrowed)));
+    assert_eq!(spath.query_nodes(&value).unwrap().len(), 3);
+}
+
+#[test]
+fn test_union_parse() {
+    assert_snapshot!(parse_error("$.a | $.b", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a | $.b
+          |     ^ the union `|` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$.a | @.b", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a | @.b
+          |       ^ expected `$`
+          |
+        "#);
+    assert_snapshot!(parse_error("$.a |", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a |
+          |      ^ expected `$`
+          |
+        "#);
+}
+
+#[test]
+fn test_pipeline() {
+    let result = eval_spath("$.users[?@.id > 0] => @.name", Extension::Pipeline);
+    assert_compact_json_snapshot!(result, @r#"["alice", "bob"]"#);
+    let result = eval_spath(
+        "$.features.* => @[?length(@) > 0] => @[*]",
+        Extension::Pipeline,
+    );
+    assert_compact_json_snapshot!(result, @r#"["beta", "web"]"#);
+    let result = eval_spath("$.users[*] => @.roles~", Extensions::all());
+    assert_compact_json_snapshot!(result, @r#"["roles", "roles"]"#);
+    let result = eval_spath(
+        "$.users[*] => @.roles.{roles: @[*]} | $.users[0] => @.id",
+        Extensions::all(),
+    );
+    assert_compact_json_snapshot!(result, @r#"[{"roles": ["admin"]}, {"roles": []}, 1]"#);
+}
+
+#[test]
+fn test_pipeline_located() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$.features[?@.enabled == true]=>@..[0]",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$.features[?@['enabled']==true] => @..[0]");
+    let paths = spath
+        .query_located(&value)
+        .unwrap()
+        .into_iter()
+        .map(|node| node.location().to_string())
+        .collect::<Vec<_>>();
+    assert_compact_json_snapshot!(paths, @r#"["$['features']['search']['tags'][0]"]"#);
+}
+
+#[test]
+fn test_pipeline_parse() {
+    assert_snapshot!(parse_error("$.a => @.b", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a => @.b
+          |     ^^ the pipeline `=>` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$.a => $.b", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.a => $.b
+          |        ^ expected `@`
+          |
+        "#);
+}
+
+#[test]
+fn test_current_key() {
+    let result = eval_spath("$.features[?@key == 'search'].tags", Extension::CurrentKey);
+    assert_compact_json_snapshot!(result, @r#"[["beta", "web"]]"#);
+    let result = eval_spath("$.users[?@key > 0].name", Extension::CurrentKey);
+    assert_compact_json_snapshot!(result, @r#"["bob"]"#);
+    let result = eval_spath("$.users[0][?length(@key) == 4]", Extension::CurrentKey);
+    assert_compact_json_snapshot!(result, @r#"["alice"]"#);
+    let result = eval_spath(
+        "$.features[?@key == 'export' || @.tags[?@key == 1]]~",
+        Extensions::all(),
+    );
+    assert_compact_json_snapshot!(result, @r#"["export", "search"]"#);
+    let result = eval_spath(
+        "$.users[?@key * 2 == 2 && @key in [1]].id",
+        Extensions::all(),
+    );
+    assert_compact_json_snapshot!(result, @"[2]");
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn test_current_key_search() {
+    let result = eval_spath(
+        "$.features[?search(@key, '^s')].enabled",
+        Extension::CurrentKey,
+    );
+    assert_compact_json_snapshot!(result, @"[true]");
+    let result = eval_spath("$.users[0][?@key =~ /^r/]", Extensions::all());
+    assert_compact_json_snapshot!(result, @r#"[["admin"]]"#);
+}
+
+#[test]
+fn test_current_key_located() {
+    let value = testdata();
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath =
+        SPath::parse_with_extensions("$..[?@key == 0]", registry, Extensions::all()).unwrap();
+    assert_snapshot!(spath, @"$..[?@key==0]");
+    let paths = spath
+        .query_located(&value)
+        .unwrap()
+        .into_iter()
+        .map(|node| node.location().to_string())
+        .collect::<Vec<_>>();
+    assert_compact_json_snapshot!(paths, @r#"["$['features']['search']['tags'][0]", "$['users'][0]", "$['users'][0]['roles'][0]"]"#);
+    assert_eq!(spath.query(&value).unwrap().len(), paths.len());
+}
+
+#[test]
+fn test_current_key_parse() {
+    assert_snapshot!(parse_error("$[?@key == 'a']", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@key == 'a']
+          |    ^ the current key `@key` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@ key == 'a']", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@ key == 'a']
+          |      ^^^ expected one of 28 possible tokens
+          |
+          = note: expected one of `^`, `..`, `.`, `[`, `=~`, `!~`, `*`, `/`, `%`, `+`, `-`, integer literal, number literal, `==`, `!=`, `<=`, `<`, `>=`, `>`, `=`, `<>`, `in`, `nin`, `contains`, `&&`, `||`, `,`, or `]`
+        "#);
+}
+
+#[test]
+fn test_current_parent() {
+    let value = json!({
+        "orders": [
+            {
+                "id": 1,
+                "currency": "USD",
+                "subtotal": {"amount": 90, "currency": "USD"},
+                "shipping": {"amount": 10, "currency": "EUR"},
+                "items": [{"sku": "a", "qty": 2}, {"sku": "b", "qty": 1}]
+            },
+            {
+                "id": 2,
+                "currency": "EUR",
+                "subtotal": {"amount": 20, "currency": "EUR"},
+                "items": [{"sku": "c", "qty": 1}]
+            }
+        ]
+    });
+    let eval = |spath, extensions: Extensions| {
+        eval_extended_spath_over_backends(spath, extensions, &value)
+    };
+
+    let result = eval(
+        "$.orders[*][?@.currency && @.currency != @^.currency]",
+        Extension::CurrentParent.into(),
+    );
+    assert_compact_json_snapshot!(result, @r#"[{"amount": 10, "currency": "EUR"}]"#);
+    let result = eval(
+        "$.orders[?@.items[?@.qty > 1 && @^[0].sku == 'a']].id",
+        Extension::CurrentParent.into(),
+    );
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval(
+        "$.orders[?length(@^) == 2 && @.id == 2].id",
+        Extension::CurrentParent.into(),
+    );
+    assert_compact_json_snapshot!(result, @"[2]");
+    let result = eval(
+        "$.orders[*].items[?@^[1]].sku",
+        Extension::CurrentParent.into(),
+    );
+    assert_compact_json_snapshot!(result, @r#"["a", "b"]"#);
+    let result = eval(
+        "$.orders[*][?@.currency == @^.currency]~",
+        Extensions::none()
+            .with(Extension::CurrentParent)
+            .with(Extension::KeySelector),
+    );
+    assert_compact_json_snapshot!(result, @r#"["subtotal", "subtotal"]"#);
+}
+
+#[test]
+fn test_current_parent_parse() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_extensions(
+        "$.orders[?@ ^ .id == 1 && @^['currency']]",
+        registry,
+        Extension::CurrentParent.into(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"$.orders[?@^['id']==1 && @^['currency']]");
+    assert_snapshot!(parse_error("$[?@^.id]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@^.id]
+          |    ^ the current parent `@^` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@^^.id]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@^^.id]
+          |      ^ expected one of 27 possible tokens
+          |
+          = note: expected one of `..`, `.`, `[`, `=~`, `!~`, `*`, `/`, `%`, `+`, `-`, integer literal, number literal, `==`, `!=`, `<=`, `<`, `>=`, `>`, `=`, `<>`, `in`, `nin`, `contains`, `&&`, `||`, `,`, or `]`
+        "#);
+}
+
+#[test]
+fn test_let_bindings() {
+    let value = json!({
+        "config": {
+            "limits": [{"tier": "basic", "max": 10}, {"tier": "gold", "max": 100}]
+        },
+        "items": [
+            {"name": "a", "price": 50, "tier": "gold"},
+            {"name": "b", "price": 500, "tier": "gold"},
+            {"name": "c", "price": 5, "tier": "basic"}
+        ]
+    });
+    let eval = |spath, extensions: Extensions| {
+        eval_extended_spath_over_backends(spath, extensions, &value)
+    };
+
+    let result = eval(
+        "let $max = value($.config.limits[?@.tier == 'gold'].max); \
+         $.items[?@.price <= $max && @.tier == 'gold'].name",
+        Extension::LetBindings.into(),
+    );
+    assert_compact_json_snapshot!(result, @r#"["a"]"#);
+    let result = eval(
+        "let $gold = 'gold'; let $max = value($.config.limits[?@.tier == $gold].max); \
+         $.items[?@.price > $max || @.tier != $gold].name",
+        Extension::LetBindings.into(),
+    );
+    assert_compact_json_snapshot!(result, @r#"["b", "c"]"#);
+    let result = eval(
+        "let $half = $.config.limits[1].max / 2; $.items[?@.price == $half].name",
+        Extensions::none()
+            .with(Extension::LetBindings)
+            .with(Extension::Arithmetic),
+    );
+    assert_compact_json_snapshot!(result, @r#"["a"]"#);
+    let result = eval(
+        "let $missing = $.nothing; $.items[?@.price == $missing || length($missing) == 0].name",
+        Extension::LetBindings.into(),
+    );
+    assert_compact_json_snapshot!(result, @"[]");
+    let result = eval(
+        "let $tier = 'basic'; $.items[?@.tier == $tier].name | $.config.limits[?@.tier == $tier].max",
+        Extensions::none()
+            .with(Extension::LetBindings)
+            .with(Extension::Union),
+    );
+    assert_compact_json_snapshot!(result, @r#"["c", 10]"#);
+}
+
+#[test]
+fn test_let_bindings_display() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    for query in [
+        "let $a=1;let $b = $a;$.items[?@.id == $b]",
+        "let $n = length($.items); $.items[?@.qty < $n]",
+        "$.a['b', 'c'].d",
+        "let $a=1;let $b = $a;$..name[?@ == $b]",
+        "$..['a', 'b']..c",
+    ] {
+        let spath =
+            SPath::parse_with_extensions(query, registry.clone(), Extensions::all()).unwrap();
+        let display = spath.to_string();
+        let reparsed =
+            SPath::parse_with_extensions(&display, registry.clone(), Extensions::all()).unwrap();
+        assert_eq!(display, reparsed.to_string());
+    }
+
+    let spath = SPath::parse_with_extensions(
+        "let $a=1;let $b = $a;$.items[?@.id == $b]",
+        registry,
+        Extensions::all(),
+    )
+    .unwrap();
+    assert_snapshot!(spath, @"let $a = 1; let $b = $a; $.items[?@['id']==$b]");
+}
+
+#[test]
+fn test_let_bindings_parse() {
+    assert_snapshot!(parse_error("let $a = 1; $[?@ == $a]", Extensions::none()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | let $a = 1; $[?@ == $a]
+          | ^^^ the let binding extension is not enabled
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?@ == $a]", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@ == $a]
+          |         ^^ binding `$a` is not defined
+          |
+        "#);
+    assert_snapshot!(parse_error("let $a = $a; $", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | let $a = $a; $
+          |          ^^ binding `$a` is not defined
+          |
+        "#);
+    assert_snapshot!(parse_error("let $a = 1; let $a = 2; $", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | let $a = 1; let $a = 2; $
+          |                 ^^ binding `$a` is already defined
+          |
+        "#);
+    assert_snapshot!(parse_error("let $a = 1 $", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | let $a = 1 $
+          |            ^ expected `;`
+          |
+        "#);
+    assert_snapshot!(parse_error("$ | let $a = 1; $", Extensions::all()), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $ | let $a = 1; $
+          |     ^^^ expected `$`
+          |
+        "#);
+}
+
+#[test]
+fn test_strict_mode() {
+    let value = json!({"a": {"b": 1, "😀": 2, "c/d": 3}, "n": [0, 1, 2]});
+    let eval = |spath, extensions: Extensions| {
+        eval_extended_spath_over_backends(spath, extensions, &value)
+    };
+
+    // valid in RFC 9535
+    let result = eval(r#"$.a['😀', "c\/d"]"#, Extensions::none());
+    assert_compact_json_snapshot!(result, @"[2, 3]");
+    let result = eval(
+        "$ .a [?@ == 1\n&& @ != 100000000000000000000]",
+        Extensions::none(),
+    );
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval(
+        "$.n[-9007199254740991:9007199254740991]",
+        Extensions::none(),
+    );
+    assert_compact_json_snapshot!(result, @"[0, 1, 2]");
+
+    // each leniency is opted in explicitly
+    let result = eval(
+        "$.a[?@ = 1 || @ <> @]",
+        Extension::AlternativeOperators.into(),
+    );
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval(r"$.a['\x62', '\142']", Extension::LenientStrings.into());
+    assert_compact_json_snapshot!(result, @"[1, 1]");
+    let result = eval("$.n[?@ == 0_1]", Extensions::all());
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval(
+        "$.n[01, -0, 9007199254740992]",
+        Extension::LenientIntegers.into(),
+    );
+    assert_compact_json_snapshot!(result, @"[1, 0]");
+    let result = eval(
+        "$[?LENGTH(@) == 3].b",
+        Extension::LenientFunctionNames.into(),
+    );
+    assert_compact_json_snapshot!(result, @"[1]");
+    let result = eval(" $. a.. b \x0C", Extension::LenientWhitespace.into());
+    assert_compact_json_snapshot!(result, @"[1]");
+}
+
+#[test]
+fn test_strict_mode_parse() {
+    let strict = |spath| parse_error(spath, Extensions::none());
+    assert_snapshot!(strict("$[?@.a = 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a = 1]
+          |        ^ the alternative operator `=` and `<>` extension is not enabled
+          |
+          = help: did you mean `==`?
+        "#);
+    assert_snapshot!(strict("$[?@.a <> 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@.a <> 1]
+          |        ^^ the alternative operator `=` and `<>` extension is not enabled
+          |
+          = help: did you mean `!=`?
+        "#);
+    assert_snapshot!(strict(r"$['\x41']"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $['\x41']
+          |   ^^^^^^ the lenient string literal extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict(r"$['\101']"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $['\101']
+          |   ^^^^^^ the lenient string literal extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$['\t']"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $['    ']
+          |   ^^ the lenient string literal extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict(r#"$["\'"]"#), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $["\'"]
+          |   ^^^^ invalid escape sequence
+          |
+        "#);
+    assert_snapshot!(strict(r"$['\uD83D']"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $['\uD83D']
+          |   ^^^^^^^^ invalid escape sequence
+          |
+        "#);
+    assert_snapshot!(strict("$[1_000]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[1_000]
+          |   ^^^^^ the digit separator `_` extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$[007]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[007]
+          |   ^^^ the lenient integer extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$[-0]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[-0]
+          |   ^^ the lenient integer extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$[9007199254740992]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[9007199254740992]
+          |   ^^^^^^^^^^^^^^^^ the lenient integer extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$[:-9007199254740992]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[:-9007199254740992]
+          |    ^^^^^^^^^^^^^^^^^ the lenient integer extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$[?@ == 01.5]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?@ == 01.5]
+          |         ^^^^ the lenient integer extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$[?Length(@) == 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?Length(@) == 1]
+          |    ^^^^^^ the lenient function name extension is not enabled
+          |
+          = help: did you mean `length`?
+        "#);
+    assert_snapshot!(strict("$[?length (@) == 1]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?length (@) == 1]
+          |          ^ the lenient whitespace extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict(" $"), @r#"
+        error: failed to parse SPath query
+          |
+        1 |  $
+          | ^ the lenient whitespace extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$ "), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $ 
+          |  ^ the lenient whitespace extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$. a"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $. a
+          |   ^ the lenient whitespace extension is not enabled
+          |
+        "#);
+    assert_snapshot!(strict("$.. a"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $.. a
+          |    ^ the lenient whitespace extension is not enabled
+          |
+        "#);
+    // form feeds are whitespace only in the lenient mode
+    assert!(strict("$[\x0C0]").contains("the lenient whitespace extension is not enabled"));
+}
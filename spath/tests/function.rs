@@ -0,0 +1,64 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "json")]
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use insta::assert_compact_json_snapshot;
+use serde_json::json;
+use spath::spec::function::Function;
+use spath::spec::function::SPathType;
+use spath::spec::function::SPathValue;
+use spath::SPath;
+
+#[test]
+fn test_stateful_function_across_threads() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let is_even = Function::<serde_json::Value>::new(
+        "is_even",
+        vec![SPathType::Value],
+        SPathType::Logical,
+        Box::new(move |mut args| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            let value = args.pop().unwrap().into_value().unwrap();
+            let even = value
+                .as_value()
+                .and_then(|v| v.as_i64())
+                .is_some_and(|v| v % 2 == 0);
+            SPathValue::Logical(even.into())
+        }),
+    );
+
+    let mut registry = spath::json::CustomFunctionRegistry::default();
+    registry.register(is_even);
+    let spath = SPath::parse_with_registry("$[?is_even(@)]", registry).unwrap();
+
+    let value = json!([1, 2, 3, 4, 5, 6]);
+    let results = std::thread::scope(|s| {
+        let handles = (0..4)
+            .map(|_| s.spawn(|| spath.query(&value).unwrap().all()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    assert_compact_json_snapshot!(results, @"[[2, 4, 6], [2, 4, 6], [2, 4, 6], [2, 4, 6]]");
+    assert_eq!(calls.load(Ordering::Relaxed), 24);
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "macros", feature = "json"))]
+
+mod common;
+
+use common::manifest_dir;
+use insta::assert_compact_json_snapshot;
+use spath::spath_function;
+use spath::spec::function::NodesType;
+use spath::NodeList;
+use spath::SPath;
+use spath::VariantValue;
+
+#[spath_function]
+fn has_prefix(s: &str, prefix: &str) -> bool {
+    s.starts_with(prefix)
+}
+
+#[spath_function(name = "concat")]
+fn concat_strings(lhs: &str, rhs: Option<&str>) -> String {
+    format!("{lhs}{}", rhs.unwrap_or_default())
+}
+
+#[spath_function]
+fn objects<T: VariantValue>(nodes: NodesType<'_, T>) -> NodesType<'_, T> {
+    nodes
+        .all()
+        .into_iter()
+        .filter(|v| v.is_object())
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn json_testdata(filename: &str) -> serde_json::Value {
+    let path = manifest_dir().join("testdata").join(filename);
+    let content = std::fs::read_to_string(path).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+fn eval_spath<'a>(
+    spath: &str,
+    value: &'a serde_json::Value,
+) -> Result<NodeList<'a, serde_json::Value>, spath::ParseError> {
+    let mut registry = spath::json::CustomFunctionRegistry::default();
+    registry
+        .register(has_prefix::function())
+        .register(concat_strings::function())
+        .register(objects::function());
+    let spath = SPath::parse_with_registry(spath, registry)?;
+    Ok(spath.query(value).unwrap())
+}
+
+#[test]
+fn test_logical_function() {
+    let value = json_testdata("rfc-9535-example-1.json");
+    let result = eval_spath(r#"$.store.book[?has_prefix(@.author, 'J')].title"#, &value).unwrap();
+    let result = result.all();
+    assert_compact_json_snapshot!(result, @r#"["The Lord of the Rings"]"#);
+    // non-string values are not matched
+    let result = eval_spath(r#"$.store.book[?has_prefix(@.price, 'J')]"#, &value).unwrap();
+    let result = result.all();
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_value_function() {
+    let value = json_testdata("rfc-9535-example-1.json");
+    let result = eval_spath(
+        r#"$.store.book[?concat(@.category, @.isbn) == 'fiction0-553-21311-3'].title"#,
+        &value,
+    )
+    .unwrap();
+    let result = result.all();
+    assert_compact_json_snapshot!(result, @r#"["Moby Dick"]"#);
+    let result = eval_spath(
+        r#"$.store.book[?concat(@.category, @.missing) == 'reference'].title"#,
+        &value,
+    )
+    .unwrap();
+    let result = result.all();
+    assert_compact_json_snapshot!(result, @r#"["Sayings of the Century"]"#);
+}
+
+#[test]
+fn test_nodes_function() {
+    let value = json_testdata("rfc-9535-example-1.json");
+    let result = eval_spath(r#"$[?count(objects(@.*)) == 1].bicycle.color"#, &value).unwrap();
+    let result = result.all();
+    assert_compact_json_snapshot!(result, @r#"["red"]"#);
+    let result = eval_spath(r#"$[?count(objects(@.*)) == 2]"#, &value).unwrap();
+    let result = result.all();
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_unregistered_function() {
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let result = SPath::parse_with_registry(r#"$[?has_prefix(@.a, 'b')]"#, registry);
+    assert!(result.is_err());
+}
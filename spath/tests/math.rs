@@ -0,0 +1,93 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "math-functions", feature = "json", feature = "toml"))]
+
+//! Conformance tests of math functions over both JSON and TOML values.
+
+mod common;
+
+use common::eval_spath_over_backends;
+use insta::assert_compact_json_snapshot;
+use serde_json::json;
+
+fn testdata() -> serde_json::Value {
+    json!({
+        "metrics": [
+            {"name": "cpu", "value": -3, "samples": [1, 2, 3, 4]},
+            {"name": "mem", "value": 2.5, "samples": [1.5, 2, "n/a", 4.5]},
+            {"name": "disk", "value": -0.5, "samples": []},
+            {"name": "net", "value": "n/a", "samples": ["n/a"]}
+        ]
+    })
+}
+
+fn eval_spath(spath: &str) -> Vec<serde_json::Value> {
+    eval_spath_over_backends(spath, &testdata())
+}
+
+#[test]
+fn test_unary_functions() {
+    let result = eval_spath(r#"$.metrics[?abs(@.value) == 3].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    let result = eval_spath(r#"$.metrics[?abs(@.value) == 0.5].name"#);
+    assert_compact_json_snapshot!(result, @r#"["disk"]"#);
+    let result = eval_spath(r#"$.metrics[?floor(@.value) == 2].name"#);
+    assert_compact_json_snapshot!(result, @r#"["mem"]"#);
+    let result = eval_spath(r#"$.metrics[?ceil(@.value) == 3].name"#);
+    assert_compact_json_snapshot!(result, @r#"["mem"]"#);
+    let result = eval_spath(r#"$.metrics[?round(@.value) == 3].name"#);
+    assert_compact_json_snapshot!(result, @r#"["mem"]"#);
+    let result = eval_spath(r#"$.metrics[?round(@.value) == -1].name"#);
+    assert_compact_json_snapshot!(result, @r#"["disk"]"#);
+    let result = eval_spath(r#"$.metrics[?round(@.value) == round(@.missing)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["net"]"#);
+}
+
+#[test]
+fn test_arithmetic_functions() {
+    let result = eval_spath(r#"$.metrics[?add(@.value, 1) == -2].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    let result = eval_spath(r#"$.metrics[?sub(@.value, 0.5) == 2].name"#);
+    assert_compact_json_snapshot!(result, @r#"["mem"]"#);
+    let result = eval_spath(r#"$.metrics[?mul(@.value, 2) == -1].name"#);
+    assert_compact_json_snapshot!(result, @r#"["disk"]"#);
+    let result = eval_spath(r#"$.metrics[?div(@.value, 2) == -1.5].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    let result = eval_spath(r#"$.metrics[?mod(@.value, 2) == -1].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    // division by zero and overflow result in nothing
+    let result = eval_spath(r#"$.metrics[?div(@.value, 0) == div(@.value, 0)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu", "mem", "disk", "net"]"#);
+    let result = eval_spath(r#"$.metrics[?mul(@.value, 9223372036854775807) < 0].name"#);
+    assert_compact_json_snapshot!(result, @r#"["disk"]"#);
+}
+
+#[test]
+fn test_array_functions() {
+    let result = eval_spath(r#"$.metrics[?sum(@.samples) == 10].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    let result = eval_spath(r#"$.metrics[?sum(@.samples) == 8].name"#);
+    assert_compact_json_snapshot!(result, @r#"["mem"]"#);
+    let result = eval_spath(r#"$.metrics[?sum(@.samples) == 0].name"#);
+    assert_compact_json_snapshot!(result, @r#"["disk", "net"]"#);
+    let result = eval_spath(r#"$.metrics[?avg(@.samples) == 2.5].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    let result = eval_spath(r#"$.metrics[?min(@.samples) == 1.5].name"#);
+    assert_compact_json_snapshot!(result, @r#"["mem"]"#);
+    let result = eval_spath(r#"$.metrics[?max(@.samples) == 4].name"#);
+    assert_compact_json_snapshot!(result, @r#"["cpu"]"#);
+    let result = eval_spath(r#"$.metrics[?max(@.samples) == max(@.name)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["disk", "net"]"#);
+}
@@ -41,7 +41,7 @@ fn eval_spath<'a>(
 ) -> Result<NodeList<'a, serde_json::Value>, spath::ParseError> {
     let registry = spath::json::BuiltinFunctionRegistry::default();
     let spath = SPath::parse_with_registry(spath, registry)?;
-    Ok(spath.query(value))
+    Ok(spath.query(value).unwrap())
 }
 
 #[test]
@@ -99,7 +99,7 @@ fn test_basic_name_selector() {
     let result = eval_spath(r#"$.o['j j']"#, &value).unwrap();
     let result = result.exactly_one().unwrap();
     assert_compact_json_snapshot!(result, @r#"{"k.k": 3}"#);
-    let result = eval_spath(r#"$.o['j j']['k.k']	"#, &value).unwrap();
+    let result = eval_spath(r#"$.o['j j']['k.k']"#, &value).unwrap();
     let result = result.exactly_one().unwrap();
     assert_compact_json_snapshot!(result, @"3");
     let result = eval_spath(r#"$.o["j j"]["k.k"]"#, &value).unwrap();
@@ -296,3 +296,12 @@ fn test_regex_functions() {
     let result = result.all();
     assert_compact_json_snapshot!(result, @"[-8, 9]");
 }
+
+#[test]
+fn test_descendant_display() {
+    for query in ["$..name", "$..*", "$..[0]", "$..['a','b']", "$.a..b.c"] {
+        let registry = spath::json::BuiltinFunctionRegistry::default();
+        let spath = SPath::<serde_json::Value>::parse_with_registry(query, registry).unwrap();
+        assert_eq!(spath.to_string(), query);
+    }
+}
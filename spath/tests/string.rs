@@ -0,0 +1,120 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "string-functions", feature = "json", feature = "toml"))]
+
+//! Conformance tests of string functions over both JSON and TOML values.
+
+mod common;
+
+use common::eval_spath_over_backends;
+use insta::assert_compact_json_snapshot;
+use insta::assert_snapshot;
+use serde_json::json;
+use spath::SPath;
+
+fn testdata() -> serde_json::Value {
+    json!({
+        "users": [
+            {"name": "Alice Smith", "email": "alice@example.com", "code": 42},
+            {"name": "bob jones", "email": "bob@example.org", "code": "B-7-X"},
+            {"name": "  Carol  ", "email": "carol@example.com", "code": true},
+            {"name": "Dave Émile", "email": "dave@example.net", "code": "D-9-Y"}
+        ]
+    })
+}
+
+fn eval_spath(spath: &str) -> Vec<serde_json::Value> {
+    eval_spath_over_backends(spath, &testdata())
+}
+
+#[test]
+fn test_predicates() {
+    let result = eval_spath(r#"$.users[?starts_with(@.email, 'bob')].name"#);
+    assert_compact_json_snapshot!(result, @r#"["bob jones"]"#);
+    let result = eval_spath(r#"$.users[?ends_with(@.email, '.com')].name"#);
+    assert_compact_json_snapshot!(result, @r#"["Alice Smith", "  Carol  "]"#);
+    let result = eval_spath(r#"$.users[?contains(@.name, 'o')].name"#);
+    assert_compact_json_snapshot!(result, @r#"["bob jones", "  Carol  "]"#);
+    let result = eval_spath(r#"$.users[?!contains(@.code, '-')].name"#);
+    assert_compact_json_snapshot!(result, @r#"["Alice Smith", "  Carol  "]"#);
+}
+
+#[test]
+fn test_transforms() {
+    let result = eval_spath(r#"$.users[?lower(@.name) == 'alice smith'].email"#);
+    assert_compact_json_snapshot!(result, @r#"["alice@example.com"]"#);
+    let result = eval_spath(r#"$.users[?upper(@.name) == 'DAVE ÉMILE'].email"#);
+    assert_compact_json_snapshot!(result, @r#"["dave@example.net"]"#);
+    let result = eval_spath(r#"$.users[?trim(@.name) == 'Carol'].email"#);
+    assert_compact_json_snapshot!(result, @r#"["carol@example.com"]"#);
+    let result = eval_spath(r#"$.users[?concat(@.name, '!') == 'bob jones!'].email"#);
+    assert_compact_json_snapshot!(result, @r#"["bob@example.org"]"#);
+    let result = eval_spath(r#"$.users[?replace(@.code, '-', '') == 'B7X'].name"#);
+    assert_compact_json_snapshot!(result, @r#"["bob jones"]"#);
+}
+
+#[test]
+fn test_indices() {
+    let result = eval_spath(r#"$.users[?substring(@.name, 0, 5) == 'Alice'].email"#);
+    assert_compact_json_snapshot!(result, @r#"["alice@example.com"]"#);
+    let result = eval_spath(r#"$.users[?substring(@.name, -5, 100) == 'Émile'].email"#);
+    assert_compact_json_snapshot!(result, @r#"["dave@example.net"]"#);
+    let result = eval_spath(r#"$.users[?split(@.email, '@', 1) == 'example.org'].name"#);
+    assert_compact_json_snapshot!(result, @r#"["bob jones"]"#);
+    let result = eval_spath(r#"$.users[?split(@.code, '-', -1) == 'Y'].name"#);
+    assert_compact_json_snapshot!(result, @r#"["Dave Émile"]"#);
+    let result = eval_spath(r#"$.users[?index_of(@.name, 'É') == 5].email"#);
+    assert_compact_json_snapshot!(result, @r#"["dave@example.net"]"#);
+    let result = eval_spath(r#"$.users[?index_of(@.name, 'z') == -1].email"#);
+    assert_compact_json_snapshot!(result, @r#"["alice@example.com", "bob@example.org", "carol@example.com", "dave@example.net"]"#);
+}
+
+#[test]
+fn test_non_string_inputs() {
+    // value type functions return nothing for non-string inputs
+    let result = eval_spath(r#"$.users[?lower(@.code) == lower(@.missing)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["Alice Smith", "  Carol  "]"#);
+    let result = eval_spath(r#"$.users[?index_of(@.code, '4') == -1].name"#);
+    assert_compact_json_snapshot!(result, @r#"["bob jones", "Dave Émile"]"#);
+    let result = eval_spath(r#"$.users[?split(@.code, '', 0) == 'B'].name"#);
+    assert_compact_json_snapshot!(result, @"[]");
+    // logical type functions return false for non-string inputs
+    let result = eval_spath(r#"$.users[?starts_with(@.code, 4)].name"#);
+    assert_compact_json_snapshot!(result, @"[]");
+}
+
+#[test]
+fn test_number_of_args() {
+    let parse_error = |spath: &str| {
+        let registry = spath::json::BuiltinFunctionRegistry::default();
+        SPath::<serde_json::Value>::parse_with_registry(spath, registry)
+            .unwrap_err()
+            .to_string()
+    };
+    assert_snapshot!(parse_error("$[?substring(@)]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?substring(@)]
+          |    ^^^^^^^^^ function 'substring' expects 3 args, but received 1
+          |
+        "#);
+    assert_snapshot!(parse_error("$[?starts_with(@, 'a', 'b')]"), @r#"
+        error: failed to parse SPath query
+          |
+        1 | $[?starts_with(@, 'a', 'b')]
+          |    ^^^^^^^^^^^ function 'starts_with' expects 2 args, but received 3
+          |
+        "#);
+}
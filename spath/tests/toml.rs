@@ -34,7 +34,7 @@ fn toml_testdata(filename: &str) -> Value {
 fn eval_spath<'a>(spath: &str, value: &'a Value) -> Result<NodeList<'a, Value>, ParseError> {
     let registry = spath::toml::BuiltinFunctionRegistry::default();
     let spath = SPath::parse_with_registry(spath, registry)?;
-    Ok(spath.query(value))
+    Ok(spath.query(value).unwrap())
 }
 
 #[test]
@@ -0,0 +1,85 @@
+// Copyright 2024 tison <wander4096@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(all(feature = "type-functions", feature = "json", feature = "toml"))]
+
+//! Conformance tests of type introspection functions over both JSON and TOML values.
+
+mod common;
+
+use common::eval_spath_over_backends;
+use insta::assert_compact_json_snapshot;
+use serde_json::json;
+use spath::SPath;
+
+fn testdata() -> serde_json::Value {
+    json!({
+        "fields": [
+            {"name": "flag", "value": true},
+            {"name": "count", "value": 42},
+            {"name": "ratio", "value": 0.5},
+            {"name": "label", "value": "text"},
+            {"name": "tags", "value": ["a", "b"]},
+            {"name": "meta", "value": {"k": "v"}},
+            {"name": "missing"}
+        ]
+    })
+}
+
+fn eval_spath(spath: &str) -> Vec<serde_json::Value> {
+    eval_spath_over_backends(spath, &testdata())
+}
+
+#[test]
+fn test_type_predicates() {
+    let result = eval_spath(r#"$.fields[?is_boolean(@.value)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["flag"]"#);
+    let result = eval_spath(r#"$.fields[?is_number(@.value)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["count", "ratio"]"#);
+    let result = eval_spath(r#"$.fields[?is_string(@.value)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["label"]"#);
+    let result = eval_spath(r#"$.fields[?is_array(@.value)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["tags"]"#);
+    let result = eval_spath(r#"$.fields[?is_object(@.value)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["meta"]"#);
+    let result = eval_spath(r#"$.fields[?!is_null(@.value)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["flag", "count", "ratio", "label", "tags", "meta", "missing"]"#);
+    let result = eval_spath(r#"$.fields[*][?is_array(@)]"#);
+    assert_compact_json_snapshot!(result, @r#"[["a", "b"]]"#);
+}
+
+#[test]
+fn test_type_of() {
+    let result = eval_spath(r#"$.fields[*].value[?type_of(@) == 'string']"#);
+    assert_compact_json_snapshot!(result, @r#"["a", "b", "v"]"#);
+    let result = eval_spath(r#"$.fields[?type_of(@.value) == 'number'].name"#);
+    assert_compact_json_snapshot!(result, @r#"["count", "ratio"]"#);
+    let result = eval_spath(r#"$..[?type_of(@) == 'object'].name"#);
+    assert_compact_json_snapshot!(result, @r#"["flag", "count", "ratio", "label", "tags", "meta", "missing"]"#);
+    let result = eval_spath(r#"$.fields[?type_of(@.value) == type_of(@.nothing)].name"#);
+    assert_compact_json_snapshot!(result, @r#"["missing"]"#);
+}
+
+#[test]
+fn test_null() {
+    let value = json!([null, 0, "null"]);
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_registry(r#"$[?is_null(@)]"#, registry).unwrap();
+    let result = spath.query(&value).unwrap().all();
+    assert_compact_json_snapshot!(result, @"[null]");
+    let registry = spath::json::BuiltinFunctionRegistry::default();
+    let spath = SPath::parse_with_registry(r#"$[?type_of(@) == 'null']"#, registry).unwrap();
+    let result = spath.query(&value).unwrap().all();
+    assert_compact_json_snapshot!(result, @"[null]");
+}
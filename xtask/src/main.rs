@@ -29,6 +29,7 @@ impl Command {
             SubCommand::Build(cmd) => cmd.run(),
             SubCommand::Lint(cmd) => cmd.run(),
             SubCommand::Test(cmd) => cmd.run(),
+            SubCommand::Cts(cmd) => cmd.run(),
         }
     }
 }
@@ -41,6 +42,8 @@ enum SubCommand {
     Lint(CommandLint),
     #[clap(about = "Run unit tests.")]
     Test(CommandTest),
+    #[clap(about = "Fetch the JSONPath Compliance Test Suite.")]
+    Cts(CommandCts),
 }
 
 #[derive(Parser)]
@@ -87,6 +90,21 @@ impl CommandLint {
     }
 }
 
+#[derive(Parser)]
+struct CommandCts {
+    #[arg(
+        long,
+        help = "The revision (tag or commit) of the compliance test suite to fetch."
+    )]
+    rev: String,
+}
+
+impl CommandCts {
+    fn run(self) {
+        run_command(make_fetch_cts_cmd(&self.rev));
+    }
+}
+
 fn find_command(cmd: &str) -> StdCommand {
     match which::which(cmd) {
         Ok(exe) => {
@@ -155,6 +173,16 @@ fn make_test_cmd(
     cmd
 }
 
+fn make_fetch_cts_cmd(rev: &str) -> StdCommand {
+    let url = format!(
+        "https://raw.githubusercontent.com/jsonpath-standard/jsonpath-compliance-test-suite/{rev}/cts.json"
+    );
+    let mut cmd = find_command("curl");
+    cmd.args(["--fail", "--location", "--silent", "--show-error"]);
+    cmd.args(["--output", "spath/testdata/cts.json", url.as_str()]);
+    cmd
+}
+
 fn make_format_cmd(fix: bool) -> StdCommand {
     let mut cmd = find_command("cargo");
     cmd.args(["fmt", "--all"]);